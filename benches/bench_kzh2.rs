@@ -43,7 +43,7 @@ fn bench(c: &mut Criterion) {
         });
 
         // commit to the polynomial
-        let com = KZH2::commit(&srs, &polynomial);
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
 
         // open the commitment
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
@@ -53,11 +53,11 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh2 opening for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH2::open(&srs, input.as_slice(), &com, &polynomial);
+                KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
             })
         });
 
-        let open = KZH2::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
         println!("kzh2 witness length in bytes: {} for degree {n}", open.compressed_size());
 
         let z = polynomial.evaluate(&input);
@@ -65,7 +65,7 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh2 verifying for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH2::verify(&srs, input.as_slice(), &z, &com, &open);
+                KZH2::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
             })
         });
     }
@@ -82,24 +82,24 @@ fn low_weight_bench(c: &mut Criterion) {
             b.iter(|| KZH2::commit(&srs, &polynomial))
         });
 
-        let com = KZH2::commit(&srs, &polynomial);
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
             .take(n)
             .collect();
 
         let bench_name = format!("kzh2 opening (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
-            b.iter(|| KZH2::open(&srs, input.as_slice(), &com, &polynomial))
+            b.iter(|| KZH2::open(&srs, input.as_slice(), &hint, &polynomial))
         });
 
-        let open = KZH2::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
         println!("kzh2 witness length in bytes (low-weight poly): {} for degree {n}", open.compressed_size());
 
         let z = polynomial.evaluate(&input);
 
         let bench_name = format!("kzh2 verifying (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
-            b.iter(|| KZH2::verify(&srs, input.as_slice(), &z, &com, &open))
+            b.iter(|| KZH2::verify(&srs, input.as_slice(), &z, &com, &open).unwrap())
         });
     }
 }
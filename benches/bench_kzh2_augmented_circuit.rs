@@ -38,7 +38,7 @@ fn bench_augmented_circuit(c: &mut Criterion) {
             let num_inputs = 10;
 
             // this generates a new instance/witness for spartan as well as PCS parameters
-            let (spartan_shape, spartan_instance, spartan_witness, spartan_key) = produce_synthetic_crr1cs::<E, KZH2<E>>(num_cons, num_vars, num_inputs);
+            let (spartan_shape, spartan_instance, spartan_witness, spartan_hint, spartan_key) = produce_synthetic_crr1cs::<E, KZH2<E>>(num_cons, num_vars, num_inputs);
 
             assert!(is_sat(&spartan_shape, &spartan_instance, &spartan_witness, &spartan_key.gens_r1cs_sat).unwrap());
 
@@ -51,6 +51,7 @@ fn bench_augmented_circuit(c: &mut Criterion) {
                 &spartan_shape,
                 &spartan_instance,
                 spartan_witness,
+                &spartan_hint,
                 &spartan_key.gens_r1cs_sat,
                 &mut prover_transcript,
             );
@@ -107,7 +108,7 @@ fn bench_augmented_circuit(c: &mut Criterion) {
                 &spartan_proof.eval_vars_at_ry,
                 &commitment_w,
                 &opening_proof,
-            );
+            ).unwrap();
 
             let (x, y) = {
                 let split_input = KZH2::split_input(&pcs_srs, &ry[1..], F::zero());
@@ -229,12 +230,13 @@ fn bench_augmented_circuit(c: &mut Criterion) {
 
             b.iter(|| {
                 // committing to the witness
-                let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+                let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
                 // spartan prover: sumchecks and stuff
                 let _ = CRR1CSProof::prove(
                     &shape,
                     &instance,
                     witness.clone(),
+                    &hint,
                     &SRS,
                     &mut new_prover_transcript,
                 );
@@ -250,7 +252,7 @@ fn bench_augmented_circuit(c: &mut Criterion) {
 
         let bench_name = format!("verify: number of poseidon calls {}", poseidon_iterations);
         c.bench_function(&bench_name, |b| {
-            let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+            let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
             let witness = CRR1CSWitness::<F>::convert(cs.clone());
 
             let mut new_prover_transcript = Transcript::new(b"example");
@@ -258,6 +260,7 @@ fn bench_augmented_circuit(c: &mut Criterion) {
                 &shape,
                 &instance,
                 witness,
+                &hint,
                 &SRS,
                 &mut new_prover_transcript,
             );
@@ -37,8 +37,8 @@ fn bench_prove(c: &mut Criterion) {
     let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
     for degree in num_variables {
         let srs = get_srs(degree);
-        let acc_1 = Accumulator2::rand(&srs, &mut thread_rng());
-        let acc_2 = Accumulator2::rand(&srs, &mut thread_rng());
+        let acc_1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc_2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
         let bench_name = format!("prove for degree n={}", degree);
         let mut transcript = Transcript::new(b"some label");
         c.bench_function(&bench_name, |b| {
@@ -53,8 +53,8 @@ fn bench_verify(c: &mut Criterion) {
     let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
     for degree in num_variables {
         let srs = get_srs(degree);
-        let acc_1 = Accumulator2::rand(&srs, &mut thread_rng());
-        let acc_2 = Accumulator2::rand(&srs, &mut thread_rng());
+        let acc_1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc_2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
         let mut prover_transcript = Transcript::new(b"some label");
         let mut verifier_transcript = prover_transcript.clone();
         let (_, _, Q) = Accumulator2::prove(&srs, &acc_1, &acc_2, &mut prover_transcript);
@@ -71,7 +71,7 @@ fn bench_decide(c: &mut Criterion) {
     let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
     for degree in num_variables {
         let srs = get_srs(degree);
-        let acc = Accumulator2::rand(&srs, &mut thread_rng());
+        let acc = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
         let bench_name = format!("decide for degrees n={}", degree);
 
         println!("kzh2-fold accumulator length in bytes: {} for degree {degree}", acc.compressed_size());
@@ -41,7 +41,7 @@ fn bench(c: &mut Criterion) {
         });
 
         // commit to the polynomial
-        let com = KZH3::commit(&srs, &polynomial);
+        let (com, hint) = KZH3::commit(&srs, &polynomial);
 
         // open the commitment
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
@@ -51,11 +51,11 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh3 opening for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH3::open(&srs, input.as_slice(), &com, &polynomial);
+                KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
             })
         });
 
-        let open = KZH3::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
         println!("kzh3 witness length in bytes: {} for degree {n}", open.compressed_size());
 
         let z = polynomial.evaluate(&input);
@@ -63,7 +63,7 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh3 verifying for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH3::verify(&srs, input.as_slice(), &z, &com, &open);
+                KZH3::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
             })
         });
     }
@@ -86,7 +86,7 @@ fn low_weight_bench(c: &mut Criterion) {
         });
 
         // commit to the polynomial
-        let com = KZH3::commit(&srs, &polynomial);
+        let (com, hint) = KZH3::commit(&srs, &polynomial);
 
         // open the commitment
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
@@ -96,11 +96,11 @@ fn low_weight_bench(c: &mut Criterion) {
         let bench_name = format!("kzh3 opening (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH3::open(&srs, input.as_slice(), &com, &polynomial);
+                KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
             })
         });
 
-        let open = KZH3::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
         println!("kzh3 witness (low-weight poly) length in bytes: {} for degree {n}", open.compressed_size());
 
         let z = polynomial.evaluate(&input);
@@ -108,7 +108,7 @@ fn low_weight_bench(c: &mut Criterion) {
         let bench_name = format!("kzh3 verifying (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH3::verify(&srs, input.as_slice(), &z, &com, &open);
+                KZH3::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
             })
         });
     }
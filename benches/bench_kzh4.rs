@@ -41,7 +41,7 @@ fn bench(c: &mut Criterion) {
         });
 
         // commit to the polynomial
-        let com = KZH4::commit(&srs, &polynomial);
+        let (com, hint) = KZH4::commit(&srs, &polynomial);
 
         // open the commitment
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
@@ -51,11 +51,11 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh4 opening for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH4::open(&srs, input.as_slice(), &com, &polynomial);
+                KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
             })
         });
 
-        let open = KZH4::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
 
         println!("kzh4 witness length in bytes: {} for degree {n}", open.compressed_size());
 
@@ -64,7 +64,7 @@ fn bench(c: &mut Criterion) {
         let bench_name = format!("kzh4 verifying for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH4::verify(&srs, input.as_slice(), &z, &com, &open);
+                KZH4::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
             })
         });
     }
@@ -87,7 +87,7 @@ fn low_weight_bench(c: &mut Criterion) {
         });
 
         // commit to the polynomial
-        let com = KZH4::commit(&srs, &polynomial);
+        let (com, hint) = KZH4::commit(&srs, &polynomial);
 
         // open the commitment
         let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
@@ -97,11 +97,11 @@ fn low_weight_bench(c: &mut Criterion) {
         let bench_name = format!("kzh4 opening (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH4::open(&srs, input.as_slice(), &com, &polynomial);
+                KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
             })
         });
 
-        let open = KZH4::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
 
         println!("kzh4 witness (low-weight poly) length in bytes: {} for degree {n}", open.compressed_size());
 
@@ -110,7 +110,7 @@ fn low_weight_bench(c: &mut Criterion) {
         let bench_name = format!("kzh4 verifying (low-weight poly) for num_variables n={}", n);
         c.bench_function(&bench_name, |b| {
             b.iter(|| {
-                KZH4::verify(&srs, input.as_slice(), &z, &com, &open);
+                KZH4::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
             })
         });
     }
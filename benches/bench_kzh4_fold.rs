@@ -0,0 +1,80 @@
+use ark_serialize::CanonicalSerialize;
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::thread_rng;
+use sqrtn_pcs::constant_for_curves::E;
+use sqrtn_pcs::kzh::KZH;
+use sqrtn_pcs::kzh::kzh4::{KZH4, KZH4SRS};
+use sqrtn_pcs::kzh_fold::kzh4_fold::{Acc4SRS, Accumulator4};
+use sqrtn_pcs::transcript::transcript::Transcript;
+
+fn get_srs(degree: usize) -> Acc4SRS<E> {
+    let srs_pcs: KZH4SRS<E> = KZH4::setup(degree, &mut thread_rng());
+
+    // return the result
+    Accumulator4::setup(srs_pcs.clone(), &mut thread_rng())
+}
+
+fn bench_prove(c: &mut Criterion) {
+    let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+    for degree in num_variables {
+        let srs = get_srs(degree);
+        let acc_1 = Accumulator4::rand(&srs);
+        let acc_2 = Accumulator4::rand(&srs);
+        let bench_name = format!("prove for degree n={}", degree);
+        let mut transcript = Transcript::new(b"some label");
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                let _ = Accumulator4::prove(&srs, &acc_1, &acc_2, &mut transcript);
+            })
+        });
+    }
+}
+
+
+fn bench_verify(c: &mut Criterion) {
+    let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+    for degree in num_variables {
+        let srs = get_srs(degree);
+        let acc_1 = Accumulator4::rand(&srs);
+        let acc_2 = Accumulator4::rand(&srs);
+        let mut prover_transcript = Transcript::new(b"some label");
+        let mut verifier_transcript = prover_transcript.clone();
+        let (_, _, proof) = Accumulator4::prove(&srs, &acc_1, &acc_2, &mut prover_transcript);
+        let bench_name = format!("verify for degrees n={}", degree);
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                let _ = Accumulator4::verify(&srs, &acc_1.instance, &acc_2.instance, &proof, &mut verifier_transcript);
+            })
+        });
+    }
+}
+
+fn bench_decide(c: &mut Criterion) {
+    let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+    for degree in num_variables {
+        let srs = get_srs(degree);
+        let acc = Accumulator4::rand(&srs);
+        let bench_name = format!("decide for degrees n={}", degree);
+
+        println!("kzh4-fold accumulator length in bytes: {} for degree {degree}", acc.compressed_size());
+
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                let _ = Accumulator4::decide(&srs, &acc);
+            })
+        });
+    }
+}
+
+fn custom_criterion_config() -> Criterion {
+    Criterion::default().sample_size(10)
+}
+
+// Benchmark group setup
+criterion_group! {
+    name = kzh4_fold_benches;
+    config = custom_criterion_config();
+    targets =  bench_verify, bench_prove, bench_decide
+}
+
+criterion_main!(kzh4_fold_benches);
@@ -0,0 +1,70 @@
+#![allow(non_snake_case)]
+#![allow(unused_imports)]
+
+use ark_serialize::CanonicalSerialize;
+use ark_std::UniformRand;
+use criterion::{Criterion, criterion_group, criterion_main};
+use rand::thread_rng;
+
+use sqrtn_pcs::constant_for_curves::{E, ScalarField as F};
+use sqrtn_pcs::kzh::KZH;
+use sqrtn_pcs::kzh::pst13::{PST13, PST13SRS};
+use sqrtn_pcs::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+
+fn bench(c: &mut Criterion) {
+    let num_variables = vec![10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20];
+    for n in num_variables {
+        // get srs
+        let srs: PST13SRS<E> = PST13::setup(n, &mut thread_rng());
+
+        // random multilinear polynomial
+        let polynomial = MultilinearPolynomial::rand(n, &mut thread_rng());
+
+        let bench_name = format!("pst13 commit for num_variables n={}", n);
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                PST13::commit(&srs, &polynomial)
+            })
+        });
+
+        // commit to the polynomial
+        let (com, hint) = PST13::commit(&srs, &polynomial);
+
+        // open the commitment
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(n)
+            .collect();
+
+        let bench_name = format!("pst13 opening for num_variables n={}", n);
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                PST13::open(&srs, input.as_slice(), &hint, &polynomial);
+            })
+        });
+
+        let open = PST13::open(&srs, input.as_slice(), &hint, &polynomial);
+        println!("pst13 witness length in bytes: {} for degree {n}", open.compressed_size());
+
+        let z = polynomial.evaluate(&input);
+
+        let bench_name = format!("pst13 verifying for num_variables n={}", n);
+        c.bench_function(&bench_name, |b| {
+            b.iter(|| {
+                PST13::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
+            })
+        });
+    }
+}
+
+fn custom_criterion_config() -> Criterion {
+    Criterion::default().sample_size(10)
+}
+
+// Benchmark group setup
+criterion_group! {
+    name = pst13_benches;
+    config = custom_criterion_config();
+    targets = bench
+}
+
+criterion_main!(pst13_benches);
@@ -0,0 +1,642 @@
+//! Multi-party updatable setup for the KZH SRS types.
+//!
+//! The ceremony starts from the SRS where every trapdoor is one (all elements are the generators),
+//! each participant then re-randomizes the current SRS with fresh secrets and publishes, for every
+//! secret x, the keys x * G1 and x * G2 with a Schnorr proof of knowledge of x bound to the SRS it
+//! updated. Anyone can check the chain of contributions with pairings: the new SRS is the previous
+//! one scaled by the published keys, and it is well-formed. As long as a single participant deleted
+//! their secrets the final trapdoors are unknown.
+//!
+//! The initial SRS has trapdoors which are known to everyone, so a ceremony without contributions
+//! is rejected, see [`crate::kzh::srs_import`] for loading an SRS from a transcript.
+//!
+//! A transcript is the compressed canonical serialization of [`Ceremony`].
+
+use crate::kzh::errors::{CeremonyError, SRSError};
+use crate::kzh::kzh2::{KZH2SRS, KZH2};
+use crate::kzh::kzh3::{KZH3SRS, KZH3};
+use crate::kzh::kzh4::{KZH4SRS, KZH4};
+use crate::kzh::{tensor, SRSCache, KZH};
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, Write};
+use ark_std::UniformRand;
+use derivative::Derivative;
+use rand::Rng;
+use rayon::prelude::*;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use std::ops::Mul;
+
+/// The public keys of a secret x of a contribution, together with a proof of knowledge of x
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct UpdateKey<E: Pairing> {
+    /// x * G1
+    pub x_g1: E::G1Affine,
+    /// x * G2
+    pub x_g2: E::G2Affine,
+    /// the commitment R = k * G1 of the Schnorr proof
+    pub R: E::G1Affine,
+    /// the response z = k + c * x of the Schnorr proof
+    pub z: E::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: Pairing, S> {
+    /// the SRS after the update
+    pub srs: S,
+    /// one key per secret, in the order expected by UpdatableSRS::update
+    pub keys: Vec<UpdateKey<E>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Ceremony<E: Pairing, S> {
+    /// the number of variables of each dimension of the SRS, it determines the initial SRS
+    pub degrees: Vec<usize>,
+    pub contributions: Vec<Contribution<E, S>>,
+}
+
+/// An SRS which can be re-randomized by the participants of a ceremony
+pub trait UpdatableSRS<E: Pairing>: Clone + CanonicalSerialize + CanonicalDeserialize {
+    /// The number of dimensions the hypercube is split into
+    const NUM_DIMENSIONS: usize;
+
+    /// The number of variables of each dimension used by `setup` for maximum_degree variables
+    fn default_degrees(maximum_degree: usize) -> Vec<usize>;
+
+    /// The SRS where every trapdoor is one, with degrees[i] variables in the i-th dimension, it is
+    /// the starting point of every ceremony
+    fn initial(degrees: &[usize]) -> Self;
+
+    /// The number of secrets a participant has to sample
+    fn num_secrets(&self) -> usize;
+
+    /// Re-randomizes the SRS with the given secrets
+    fn update(&self, secrets: &[E::ScalarField]) -> Self;
+
+    /// Checks with pairings that self is prev updated with the secrets behind the keys and that it
+    /// is well-formed, the keys themselves are assumed to be checked already
+    fn check_update<R: Rng>(&self, prev: &Self, keys: &[UpdateKey<E>], rng: &mut R) -> bool;
+}
+
+impl<E: Pairing, S: UpdatableSRS<E>> Ceremony<E, S> {
+    /// Starts a ceremony for an SRS with the same split of the variables as `setup`
+    pub fn new(maximum_degree: usize) -> Self {
+        Self::with_degrees(S::default_degrees(maximum_degree))
+    }
+
+    /// Starts a ceremony for an SRS with an explicit split of the variables, as in `setup_with_degrees`
+    pub fn with_degrees(degrees: Vec<usize>) -> Self {
+        assert_eq!(degrees.len(), S::NUM_DIMENSIONS, "there should be one degree per dimension");
+
+        Ceremony {
+            degrees,
+            contributions: Vec::new(),
+        }
+    }
+
+    /// Returns the SRS after the last contribution
+    pub fn srs(&self) -> S {
+        match self.contributions.last() {
+            Some(contribution) => contribution.srs.clone(),
+            None => S::initial(self.degrees.as_slice()),
+        }
+    }
+
+    /// Adds a contribution with fresh secrets, the secrets are dropped once the function returns
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        let prev = self.srs();
+        let digest = srs_digest(&prev);
+
+        let secrets: Vec<E::ScalarField> = (0..prev.num_secrets()).map(|_| E::ScalarField::rand(rng)).collect();
+        let keys = secrets.iter()
+            .enumerate()
+            .map(|(index, x)| UpdateKey::new(x, digest.as_slice(), index, rng))
+            .collect();
+
+        self.contributions.push(Contribution {
+            srs: prev.update(secrets.as_slice()),
+            keys,
+        });
+    }
+
+    /// Verifies the whole chain of contributions starting from the initial SRS, there must be at
+    /// least one contribution
+    pub fn verify<R: Rng>(&self, rng: &mut R) -> Result<(), CeremonyError> {
+        if self.degrees.len() != S::NUM_DIMENSIONS {
+            return Err(CeremonyError::InvalidDegrees);
+        }
+        if self.contributions.is_empty() {
+            return Err(CeremonyError::NoContributions);
+        }
+
+        let initial = S::initial(self.degrees.as_slice());
+
+        let mut prev = &initial;
+        for (contribution_index, contribution) in self.contributions.iter().enumerate() {
+            let digest = srs_digest(prev);
+
+            if contribution.keys.len() != prev.num_secrets()
+                || !UpdateKey::verify_all(contribution.keys.as_slice(), digest.as_slice(), rng) {
+                return Err(CeremonyError::InvalidProofOfKnowledge { contribution: contribution_index });
+            }
+
+            if !contribution.srs.check_update(prev, contribution.keys.as_slice(), rng) {
+                return Err(CeremonyError::InvalidUpdate { contribution: contribution_index });
+            }
+
+            prev = &contribution.srs;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the transcript of the ceremony
+    pub fn save<W: Write>(&self, writer: W) -> Result<(), SRSError> {
+        self.serialize_compressed(writer)?;
+        Ok(())
+    }
+
+    /// Reads the transcript of a ceremony, it is only returned if all contributions verify
+    pub fn load<Rd: Read, R: Rng>(reader: Rd, rng: &mut R) -> Result<Self, SRSError> {
+        let ceremony = Self::deserialize_compressed(reader)?;
+        ceremony.verify(rng)?;
+        Ok(ceremony)
+    }
+}
+
+impl<E: Pairing> UpdateKey<E> {
+    fn new<R: Rng>(x: &E::ScalarField, digest: &[u8], index: usize, rng: &mut R) -> Self {
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+
+        let x_g1 = g1.mul(*x).into_affine();
+        let x_g2 = g2.mul(*x).into_affine();
+
+        let k = E::ScalarField::rand(rng);
+        let R = g1.mul(k).into_affine();
+        let c = pok_challenge::<E>(digest, index, &x_g1, &x_g2, &R);
+
+        UpdateKey { x_g1, x_g2, R, z: k + c * x }
+    }
+
+    /// Checks that no key is trivial, that x_g1 and x_g2 have the same discrete log and the
+    /// proofs of knowledge
+    fn verify_all<R: Rng>(keys: &[Self], digest: &[u8], rng: &mut R) -> bool {
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+
+        if keys.iter().any(|key| key.x_g1.is_zero()) {
+            return false;
+        }
+
+        // z * G1 = R + c * x_g1
+        for (index, key) in keys.iter().enumerate() {
+            let c = pok_challenge::<E>(digest, index, &key.x_g1, &key.x_g2, &key.R);
+            if g1.mul(key.z) != key.R.into_group() + key.x_g1.mul(c) {
+                return false;
+            }
+        }
+
+        // e(sum_i r_i * x_g1[i], G2) = e(G1, sum_i r_i * x_g2[i])
+        let r = random_scalars::<E, _>(keys.len(), rng);
+        let x_g1: Vec<_> = keys.iter().map(|key| key.x_g1).collect();
+        let x_g2: Vec<_> = keys.iter().map(|key| key.x_g2).collect();
+        let lhs = E::G1::msm_unchecked(x_g1.as_slice(), r.as_slice());
+        let rhs = E::G2::msm_unchecked(x_g2.as_slice(), r.as_slice());
+
+        E::multi_pairing([lhs, -g1.into_group()], [g2.into_group(), rhs]).0.is_one()
+    }
+}
+
+/// Fiat-Shamir challenge of the Schnorr proof of the index-th key of a contribution
+fn pok_challenge<E: Pairing>(
+    digest: &[u8],
+    index: usize,
+    x_g1: &E::G1Affine,
+    x_g2: &E::G2Affine,
+    R: &E::G1Affine,
+) -> E::ScalarField {
+    let mut buf = vec![];
+    x_g1.serialize_compressed(&mut buf).unwrap();
+    x_g2.serialize_compressed(&mut buf).unwrap();
+    R.serialize_compressed(&mut buf).unwrap();
+
+    let mut shake = sha3::Shake256::default();
+    shake.update(b"KZH ceremony proof of knowledge");
+    shake.update(digest);
+    shake.update(&(index as u64).to_le_bytes());
+    shake.update(&buf);
+
+    let mut reader = shake.finalize_xof();
+    let mut bytes = [0u8; 64];
+    reader.read(&mut bytes);
+    E::ScalarField::from_le_bytes_mod_order(&bytes)
+}
+
+/// Hash of the SRS being updated, the proofs of knowledge are bound to it so keys can't be replayed
+fn srs_digest<S: CanonicalSerialize>(srs: &S) -> Vec<u8> {
+    let mut buf = vec![];
+    srs.serialize_compressed(&mut buf).unwrap();
+
+    let mut shake = sha3::Shake256::default();
+    shake.update(&buf);
+
+    let mut reader = shake.finalize_xof();
+    let mut digest = vec![0u8; 32];
+    reader.read(&mut digest);
+    digest
+}
+
+fn random_scalars<E: Pairing, R: Rng>(n: usize, rng: &mut R) -> Vec<E::ScalarField> {
+    (0..n).map(|_| E::ScalarField::rand(rng)).collect()
+}
+
+/// Returns [scalars[i] * points[i]]
+fn scale<G: CurveGroup>(points: &[G::Affine], scalars: &[G::ScalarField]) -> Vec<G::Affine> {
+    assert_eq!(points.len(), scalars.len());
+    let scaled: Vec<G> = points.par_iter()
+        .zip(scalars.par_iter())
+        .map(|(p, s)| p.mul(*s))
+        .collect();
+    G::normalize_batch(scaled.as_slice())
+}
+
+/// Checks new[i] = x_i * old[i] for G1 elements, where x_i is the secret of keys[i]
+fn check_g1_update<E: Pairing, R: Rng>(old: &[E::G1Affine], new: &[E::G1Affine], keys: &[UpdateKey<E>], rng: &mut R) -> bool {
+    if old.len() != new.len() || old.len() != keys.len() {
+        return false;
+    }
+
+    // e(sum_i r_i * new[i], G2) = prod_i e(r_i * old[i], x_g2[i])
+    let r = random_scalars::<E, _>(old.len(), rng);
+    let g1_elems: Vec<E::G1> = std::iter::once(-E::G1::msm_unchecked(new, r.as_slice()))
+        .chain(old.iter().zip(r.iter()).map(|(p, r)| p.mul(*r)))
+        .collect();
+    let g2_elems: Vec<E::G2Affine> = std::iter::once(E::G2Affine::generator())
+        .chain(keys.iter().map(|key| key.x_g2))
+        .collect();
+
+    E::multi_pairing(g1_elems, g2_elems).0.is_one()
+}
+
+/// Checks new[i] = x_i * old[i] for G2 elements, where x_i is the secret of keys[i]
+fn check_g2_update<E: Pairing, R: Rng>(old: &[E::G2Affine], new: &[E::G2Affine], keys: &[UpdateKey<E>], rng: &mut R) -> bool {
+    if old.len() != new.len() || old.len() != keys.len() {
+        return false;
+    }
+
+    // e(G1, sum_i r_i * new[i]) = prod_i e(r_i * x_g1[i], old[i])
+    let r = random_scalars::<E, _>(old.len(), rng);
+    let g1_elems: Vec<E::G1> = std::iter::once(-E::G1Affine::generator().into_group())
+        .chain(keys.iter().zip(r.iter()).map(|(key, r)| key.x_g1.mul(*r)))
+        .collect();
+    let g2_elems: Vec<E::G2> = std::iter::once(E::G2::msm_unchecked(new, r.as_slice()))
+        .chain(old.iter().map(|p| p.into_group()))
+        .collect();
+
+    E::multi_pairing(g1_elems, g2_elems).0.is_one()
+}
+
+/// The secrets are tau' (degree_x), alpha', gamma (degree_y) and epsilon, the update maps
+/// tau_i -> tau_i * tau'_i, alpha -> alpha * alpha', G_j -> G_j * gamma_j / alpha' and the blinding
+/// generator H -> H * epsilon / alpha', so that H_y[j] and H_y_blind are scaled by gamma_j and epsilon
+impl<E: Pairing> UpdatableSRS<E> for KZH2SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    const NUM_DIMENSIONS: usize = 2;
+
+    fn default_degrees(maximum_degree: usize) -> Vec<usize> {
+        let (degree_x, degree_y) = KZH2::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        vec![degree_x, degree_y]
+    }
+
+    fn initial(degrees: &[usize]) -> Self {
+        let (degree_x, degree_y) = (1 << degrees[0], 1 << degrees[1]);
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+
+        KZH2SRS {
+            degree_x,
+            degree_y,
+            H_xy: vec![vec![g1; degree_y]; degree_x],
+            H_y: vec![g1; degree_y],
+            V_x: vec![g2.into_group(); degree_x],
+            V_prime: g2.into_group(),
+            H_x_blind: vec![g1; degree_x],
+            H_y_blind: g1,
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn num_secrets(&self) -> usize {
+        self.degree_x + self.degree_y + 2
+    }
+
+    fn update(&self, secrets: &[E::ScalarField]) -> Self {
+        assert_eq!(secrets.len(), self.num_secrets());
+
+        let (tau, rest) = secrets.split_at(self.degree_x);
+        let (alpha, rest) = (rest[0], &rest[1..]);
+        let (gamma, epsilon) = (&rest[..self.degree_y], rest[self.degree_y]);
+
+        let alpha_inverse = alpha.inverse().unwrap();
+        let gamma_over_alpha: Vec<_> = gamma.iter().map(|g| *g * alpha_inverse).collect();
+        let tau_times_epsilon_over_alpha: Vec<_> = tau.iter().map(|t| *t * epsilon * alpha_inverse).collect();
+
+        let H_xy = scale::<E::G1>(self.H_xy.concat().as_slice(), tensor(tau, gamma_over_alpha.as_slice()).as_slice())
+            .chunks(self.degree_y)
+            .map(|row| row.to_vec())
+            .collect();
+
+        KZH2SRS {
+            degree_x: self.degree_x,
+            degree_y: self.degree_y,
+            H_xy,
+            H_y: scale::<E::G1>(self.H_y.as_slice(), gamma),
+            V_x: self.V_x.iter().zip(tau.iter()).map(|(v, t)| *v * t).collect(),
+            V_prime: self.V_prime * alpha,
+            H_x_blind: scale::<E::G1>(self.H_x_blind.as_slice(), tau_times_epsilon_over_alpha.as_slice()),
+            H_y_blind: self.H_y_blind.mul(epsilon).into_affine(),
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn check_update<R: Rng>(&self, prev: &Self, keys: &[UpdateKey<E>], rng: &mut R) -> bool {
+        if self.degree_x != prev.degree_x
+            || self.degree_y != prev.degree_y
+            || keys.len() != prev.num_secrets() {
+            return false;
+        }
+
+        let (tau_keys, rest) = keys.split_at(self.degree_x);
+        let (alpha_keys, rest) = rest.split_at(1);
+        let (gamma_keys, epsilon_keys) = rest.split_at(self.degree_y);
+
+        let V_x = E::G2::normalize_batch(self.V_x.as_slice());
+        let V_prime = self.V_prime.into_affine();
+
+        // the elements which are directly scaled by a secret
+        check_g2_update(E::G2::normalize_batch(prev.V_x.as_slice()).as_slice(), V_x.as_slice(), tau_keys, rng)
+            && check_g2_update(&[prev.V_prime.into_affine()], &[V_prime], alpha_keys, rng)
+            && check_g1_update(prev.H_y.as_slice(), self.H_y.as_slice(), gamma_keys, rng)
+            && check_g1_update(&[prev.H_y_blind], &[self.H_y_blind], epsilon_keys, rng)
+            // the remaining elements are determined by the structure of the SRS
+            && self.check_well_formed(rng).is_ok()
+    }
+}
+
+/// The secrets are tau_x' (degree_x), tau_y' (degree_y) and tau_z' (degree_z)
+impl<E: Pairing> UpdatableSRS<E> for KZH3SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    const NUM_DIMENSIONS: usize = 3;
+
+    fn default_degrees(maximum_degree: usize) -> Vec<usize> {
+        let (degree_x, degree_y, degree_z) = KZH3::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        vec![degree_x, degree_y, degree_z]
+    }
+
+    fn initial(degrees: &[usize]) -> Self {
+        let (degree_x, degree_y, degree_z) = (1 << degrees[0], 1 << degrees[1], 1 << degrees[2]);
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+
+        KZH3SRS {
+            degree_x,
+            degree_y,
+            degree_z,
+            H_xyz: vec![g1; degree_x * degree_y * degree_z],
+            H_yz: vec![g1; degree_y * degree_z],
+            H_z: vec![g1; degree_z],
+            V_x: vec![g2; degree_x],
+            V_y: vec![g2; degree_y],
+            V_z: vec![g2; degree_z],
+            v: g2,
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn num_secrets(&self) -> usize {
+        self.degree_x + self.degree_y + self.degree_z
+    }
+
+    fn update(&self, secrets: &[E::ScalarField]) -> Self {
+        assert_eq!(secrets.len(), self.num_secrets());
+
+        let (tau_x, rest) = secrets.split_at(self.degree_x);
+        let (tau_y, tau_z) = rest.split_at(self.degree_y);
+        let tau_yz = tensor(tau_y, tau_z);
+
+        KZH3SRS {
+            degree_x: self.degree_x,
+            degree_y: self.degree_y,
+            degree_z: self.degree_z,
+            H_xyz: scale::<E::G1>(self.H_xyz.as_slice(), tensor(tau_x, tau_yz.as_slice()).as_slice()),
+            H_yz: scale::<E::G1>(self.H_yz.as_slice(), tau_yz.as_slice()),
+            H_z: scale::<E::G1>(self.H_z.as_slice(), tau_z),
+            V_x: scale::<E::G2>(self.V_x.as_slice(), tau_x),
+            V_y: scale::<E::G2>(self.V_y.as_slice(), tau_y),
+            V_z: scale::<E::G2>(self.V_z.as_slice(), tau_z),
+            v: self.v,
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn check_update<R: Rng>(&self, prev: &Self, keys: &[UpdateKey<E>], rng: &mut R) -> bool {
+        if self.degree_x != prev.degree_x
+            || self.degree_y != prev.degree_y
+            || self.degree_z != prev.degree_z
+            || self.v != prev.v
+            || keys.len() != prev.num_secrets() {
+            return false;
+        }
+
+        let (tau_x_keys, rest) = keys.split_at(self.degree_x);
+        let (tau_y_keys, tau_z_keys) = rest.split_at(self.degree_y);
+
+        // the elements which are directly scaled by a secret
+        check_g2_update(prev.V_x.as_slice(), self.V_x.as_slice(), tau_x_keys, rng)
+            && check_g2_update(prev.V_y.as_slice(), self.V_y.as_slice(), tau_y_keys, rng)
+            && check_g2_update(prev.V_z.as_slice(), self.V_z.as_slice(), tau_z_keys, rng)
+            && check_g1_update(prev.H_z.as_slice(), self.H_z.as_slice(), tau_z_keys, rng)
+            // the remaining elements are determined by the structure of the SRS
+            && self.check_well_formed(rng).is_ok()
+    }
+}
+
+/// The secrets are tau_x' (degree_x), tau_y' (degree_y), tau_z' (degree_z) and tau_t' (degree_t)
+impl<E: Pairing> UpdatableSRS<E> for KZH4SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    const NUM_DIMENSIONS: usize = 4;
+
+    fn default_degrees(maximum_degree: usize) -> Vec<usize> {
+        let (degree_x, degree_y, degree_z, degree_t) = KZH4::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        vec![degree_x, degree_y, degree_z, degree_t]
+    }
+
+    fn initial(degrees: &[usize]) -> Self {
+        let (degree_x, degree_y, degree_z, degree_t) = (1 << degrees[0], 1 << degrees[1], 1 << degrees[2], 1 << degrees[3]);
+        let (g1, g2) = (E::G1Affine::generator(), E::G2Affine::generator());
+
+        KZH4SRS {
+            degree_x,
+            degree_y,
+            degree_z,
+            degree_t,
+            H_xyzt: vec![g1; degree_x * degree_y * degree_z * degree_t],
+            H_yzt: vec![g1; degree_y * degree_z * degree_t],
+            H_zt: vec![g1; degree_z * degree_t],
+            H_t: vec![g1; degree_t],
+            V_x: vec![g2; degree_x],
+            V_y: vec![g2; degree_y],
+            V_z: vec![g2; degree_z],
+            V_t: vec![g2; degree_t],
+            v: g2,
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn num_secrets(&self) -> usize {
+        self.degree_x + self.degree_y + self.degree_z + self.degree_t
+    }
+
+    fn update(&self, secrets: &[E::ScalarField]) -> Self {
+        assert_eq!(secrets.len(), self.num_secrets());
+
+        let (tau_x, rest) = secrets.split_at(self.degree_x);
+        let (tau_y, rest) = rest.split_at(self.degree_y);
+        let (tau_z, tau_t) = rest.split_at(self.degree_z);
+        let tau_zt = tensor(tau_z, tau_t);
+        let tau_yzt = tensor(tau_y, tau_zt.as_slice());
+
+        KZH4SRS {
+            degree_x: self.degree_x,
+            degree_y: self.degree_y,
+            degree_z: self.degree_z,
+            degree_t: self.degree_t,
+            H_xyzt: scale::<E::G1>(self.H_xyzt.as_slice(), tensor(tau_x, tau_yzt.as_slice()).as_slice()),
+            H_yzt: scale::<E::G1>(self.H_yzt.as_slice(), tau_yzt.as_slice()),
+            H_zt: scale::<E::G1>(self.H_zt.as_slice(), tau_zt.as_slice()),
+            H_t: scale::<E::G1>(self.H_t.as_slice(), tau_t),
+            V_x: scale::<E::G2>(self.V_x.as_slice(), tau_x),
+            V_y: scale::<E::G2>(self.V_y.as_slice(), tau_y),
+            V_z: scale::<E::G2>(self.V_z.as_slice(), tau_z),
+            V_t: scale::<E::G2>(self.V_t.as_slice(), tau_t),
+            v: self.v,
+            prepared: SRSCache::default(),
+        }
+    }
+
+    fn check_update<R: Rng>(&self, prev: &Self, keys: &[UpdateKey<E>], rng: &mut R) -> bool {
+        if self.degree_x != prev.degree_x
+            || self.degree_y != prev.degree_y
+            || self.degree_z != prev.degree_z
+            || self.degree_t != prev.degree_t
+            || self.v != prev.v
+            || keys.len() != prev.num_secrets() {
+            return false;
+        }
+
+        let (tau_x_keys, rest) = keys.split_at(self.degree_x);
+        let (tau_y_keys, rest) = rest.split_at(self.degree_y);
+        let (tau_z_keys, tau_t_keys) = rest.split_at(self.degree_z);
+
+        // the elements which are directly scaled by a secret
+        check_g2_update(prev.V_x.as_slice(), self.V_x.as_slice(), tau_x_keys, rng)
+            && check_g2_update(prev.V_y.as_slice(), self.V_y.as_slice(), tau_y_keys, rng)
+            && check_g2_update(prev.V_z.as_slice(), self.V_z.as_slice(), tau_z_keys, rng)
+            && check_g2_update(prev.V_t.as_slice(), self.V_t.as_slice(), tau_t_keys, rng)
+            && check_g1_update(prev.H_t.as_slice(), self.H_t.as_slice(), tau_t_keys, rng)
+            // the remaining elements are determined by the structure of the SRS
+            && self.check_well_formed(rng).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use rand::thread_rng;
+
+    fn test_ceremony_helper<PC: KZH<E>>(mut ceremony: Ceremony<E, PC::SRS>)
+    where
+        PC::SRS: UpdatableSRS<E>,
+    {
+        let num_vars: usize = ceremony.degrees.iter().sum();
+
+        // simulate three contributors
+        for _ in 0..3 {
+            ceremony.contribute(&mut thread_rng());
+        }
+        ceremony.verify(&mut thread_rng()).unwrap();
+
+        // round trip through the transcript format
+        let mut bytes = Vec::new();
+        ceremony.save(&mut bytes).unwrap();
+        let ceremony = Ceremony::<E, PC::SRS>::load(bytes.as_slice(), &mut thread_rng()).unwrap();
+
+        // the final SRS is usable
+        let srs = ceremony.srs();
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+        let (com, hint) = PC::commit(&srs, &polynomial);
+        let open = PC::open(&srs, input.as_slice(), &hint, &polynomial);
+        PC::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_ceremony() {
+        test_ceremony_helper::<KZH2<E>>(Ceremony::new(8));
+        test_ceremony_helper::<KZH3<E>>(Ceremony::new(8));
+        test_ceremony_helper::<KZH4<E>>(Ceremony::new(8));
+    }
+
+    #[test]
+    fn test_ceremony_with_degrees() {
+        test_ceremony_helper::<KZH2<E>>(Ceremony::with_degrees(vec![2, 6]));
+        test_ceremony_helper::<KZH3<E>>(Ceremony::with_degrees(vec![1, 2, 5]));
+        test_ceremony_helper::<KZH4<E>>(Ceremony::with_degrees(vec![1, 3, 2, 2]));
+
+        // the SRS has the requested shape rather than the balanced one
+        let mut ceremony = Ceremony::<E, KZH2SRS<E>>::with_degrees(vec![2, 6]);
+        ceremony.contribute(&mut thread_rng());
+        let srs = ceremony.srs();
+        assert_eq!((srs.degree_x, srs.degree_y), (4, 64));
+
+        // a transcript with degrees for another number of dimensions is rejected
+        let mut tampered = ceremony.clone();
+        tampered.degrees.push(1);
+        assert_eq!(tampered.verify(&mut thread_rng()), Err(CeremonyError::InvalidDegrees));
+    }
+
+    #[test]
+    fn test_invalid_contributions() {
+        let mut ceremony = Ceremony::<E, KZH2SRS<E>>::new(6);
+        assert_eq!(ceremony.verify(&mut thread_rng()), Err(CeremonyError::NoContributions));
+
+        for _ in 0..2 {
+            ceremony.contribute(&mut thread_rng());
+        }
+
+        // a malformed SRS element is caught by the structural check
+        let mut tampered = ceremony.clone();
+        tampered.contributions[1].srs.H_xy[1][2] = tampered.contributions[1].srs.H_xy[1][3];
+        assert_eq!(tampered.verify(&mut thread_rng()), Err(CeremonyError::InvalidUpdate { contribution: 1 }));
+
+        // an SRS which is not the update of the previous one is caught
+        let mut tampered = ceremony.clone();
+        tampered.contributions[1].srs = ceremony.contributions[0].srs.clone();
+        assert_eq!(tampered.verify(&mut thread_rng()), Err(CeremonyError::InvalidUpdate { contribution: 1 }));
+
+        // keys can't be replayed on another SRS
+        let mut tampered = ceremony.clone();
+        tampered.contributions[0].keys = ceremony.contributions[1].keys.clone();
+        assert_eq!(tampered.verify(&mut thread_rng()), Err(CeremonyError::InvalidProofOfKnowledge { contribution: 0 }));
+    }
+}
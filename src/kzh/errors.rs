@@ -0,0 +1,122 @@
+use thiserror::Error;
+
+/// Represents a failure while verifying a KZH opening proof, the variant tells which check failed.
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum PCSError {
+    /// The pairing check binding the opening to the commitment failed.
+    #[error("KZH pairing check failed")]
+    PairingCheckFailed,
+    /// The MSM check binding the partial evaluation to the opening failed.
+    #[error("KZH MSM check failed")]
+    MSMCheckFailed,
+    /// The final evaluation of the partial polynomial does not match the claimed output.
+    #[error("KZH final evaluation check failed")]
+    EvaluationCheckFailed,
+    /// The sumcheck reducing several evaluation points to a single one failed.
+    #[error("KZH batching sumcheck failed")]
+    SumcheckFailed,
+    /// The index of a boolean point is larger than the number of evaluations the SRS supports.
+    #[error("KZH boolean point index out of range")]
+    IndexOutOfRange,
+    /// The batch has no commitment or not one claimed evaluation per commitment.
+    #[error("KZH batch is empty or its sizes do not match")]
+    InvalidBatchSize,
+    /// The SRS does not have the shape the scheme expects, e.g. it has another number of dimensions.
+    #[error("KZH SRS does not match the scheme")]
+    InvalidSRS,
+}
+
+/// Represents a failure while importing a KZH SRS from the output of a ceremony.
+#[derive(Error, Debug)]
+pub enum SRSError {
+    /// The SRS elements are not related to each other through the same trapdoors.
+    #[error("SRS is not well-formed")]
+    MalformedSRS,
+    /// The ceremony output could not be read.
+    #[error("failed to read ceremony output: {0}")]
+    SerializationError(#[from] ark_serialize::SerializationError),
+    /// The SRS comes from a ceremony transcript which does not verify.
+    #[error("invalid ceremony transcript: {0}")]
+    InvalidCeremony(#[from] CeremonyError),
+    /// The accumulator generators are not the ones derived from the given label.
+    #[error("accumulator generators are not derived from the label")]
+    InvalidGenerators,
+    /// The SRS is trimmed to more variables than it supports, in total or in some dimension.
+    #[error("the trimmed degrees exceed the degrees of the SRS")]
+    TrimmedDegreeTooLarge,
+}
+
+/// Represents a failure while verifying the transcript of an SRS contribution ceremony, the
+/// index of the faulty contribution is reported.
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum CeremonyError {
+    /// The ceremony has no contribution, so its SRS is the initial one whose trapdoors are all one.
+    #[error("the ceremony has no contribution")]
+    NoContributions,
+    /// The ceremony does not have one number of variables per dimension of the SRS.
+    #[error("the ceremony degrees do not match the dimensions of the SRS")]
+    InvalidDegrees,
+    /// A public key of the contribution is malformed or its proof of knowledge does not verify.
+    #[error("invalid proof of knowledge in contribution {contribution}")]
+    InvalidProofOfKnowledge { contribution: usize },
+    /// The SRS of the contribution is not the previous SRS updated with the published keys.
+    #[error("invalid update in contribution {contribution}")]
+    InvalidUpdate { contribution: usize },
+}
+
+/// Represents a failure of the ark-poly-commit adapter of the KZH schemes.
+#[derive(Error, Debug)]
+pub enum PolyCommitError {
+    /// An error reported by ark-poly-commit itself, e.g. an unsupported degree bound.
+    #[error(transparent)]
+    PolyCommit(#[from] ark_poly_commit::Error),
+    /// The number of variables was not given to the setup of a multilinear scheme.
+    #[error("the number of variables is required for multilinear polynomials")]
+    MissingNumVars,
+    /// The polynomial has more variables than the SRS supports.
+    #[error("polynomial has {num_variables} variables, the srs supports at most {max_num_variables}")]
+    TooManyVariables { num_variables: usize, max_num_variables: usize },
+    /// The evaluation point does not have one coordinate per variable of the polynomial.
+    #[error("point has {point_length} coordinates, the polynomial has {num_variables} variables")]
+    PointLengthMismatch { point_length: usize, num_variables: usize },
+    /// The numbers of polynomials, commitments, states and values of a query do not match.
+    #[error("the numbers of polynomials, commitments, states and values do not match")]
+    InputLengthMismatch,
+    /// KZH commitments are not hiding through this adapter.
+    #[error("hiding commitments are not supported")]
+    HidingUnsupported,
+    /// The commitment is the empty placeholder commitment.
+    #[error("empty commitment")]
+    EmptyCommitment,
+}
+
+/// Represents a failure while folding several accumulators in a single round.
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum FoldingError {
+    /// Less than two accumulators were given to the folding.
+    #[error("at least two accumulators are needed to fold, got {0}")]
+    TooFewAccumulators(usize),
+    /// The proof does not have one error term per accumulator but one.
+    #[error("expected {expected} error terms, got {found}")]
+    InvalidErrorTermCount { expected: usize, found: usize },
+}
+
+/// Represents a failure while loading a checkpoint of the running accumulator of a prover.
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// The data does not start with the checkpoint magic bytes.
+    #[error("not an accumulator checkpoint")]
+    InvalidMagic,
+    /// The checkpoint was written with a format version this library does not read.
+    #[error("unsupported checkpoint version {0}")]
+    UnsupportedVersion(u32),
+    /// The checkpoint holds an accumulator of another KZH dimension.
+    #[error("checkpoint holds a KZH{found} accumulator, expected KZH{expected}")]
+    DimensionMismatch { expected: u32, found: u32 },
+    /// The accumulator does not match the sizes of the SRS or is not satisfied.
+    #[error("the accumulator of the checkpoint is not satisfied")]
+    UnsatisfiedAccumulator,
+    /// The checkpoint could not be read or written.
+    #[error("failed to (de)serialize checkpoint: {0}")]
+    SerializationError(#[from] ark_serialize::SerializationError),
+}
@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
-use crate::kzh::KZH;
+use crate::kzh::errors::{PCSError, SRSError};
+use crate::kzh::{bisect_failures, check_level, combine_columns, get_batch_randomizers, split_input_by_degrees, trimmed_degree, trimmed_degrees, CostReport, Homomorphic, SRSCache, KZH};
 use crate::math::Math;
 use crate::nexus_spartan::commitment_traits::ToAffine;
 use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
@@ -9,14 +10,16 @@ use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::pairing::Pairing;
 use ark_ec::AffineRepr;
 use ark_ec::{CurveGroup, VariableBaseMSM};
-use ark_ff::{AdditiveGroup, PrimeField, Zero};
+use ark_ff::{AdditiveGroup, One, PrimeField, Zero};
 use ark_serialize::Valid;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::UniformRand;
 use derivative::Derivative;
 use rand::{Rng};
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
+use std::collections::BTreeMap;
+use std::io::Read;
 use std::ops::{Add, Mul};
 
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
@@ -32,6 +35,13 @@ pub struct KZH2SRS<E: Pairing> {
     pub V_x: Vec<E::G2>,
 
     pub V_prime: E::G2,
+
+    /// tau_i * H for a blinding generator H, used to blind the commitment C
+    pub H_x_blind: Vec<E::G1Affine>,
+    /// alpha * H, used to blind the row commitments aux and the partial evaluation f_star
+    pub H_y_blind: E::G1Affine,
+    /// the prepared verifier key, computed on the first verification, see `prepared_verifier_key`
+    pub prepared: SRSCache<KZH2PreparedVerifierKey<E>>,
 }
 
 #[derive(
@@ -47,6 +57,20 @@ pub struct KZH2SRS<E: Pairing> {
 pub struct KZH2Commitment<E: Pairing> {
     /// the commitment C to the polynomial
     pub C: E::G1Affine,
+}
+
+/// Prover-only data of a commitment, kept by the prover to open it and never sent to the verifier
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct KZH2CommitmentHint<E: Pairing> {
     /// auxiliary data which is in fact Pedersen commitments to rows of the polynomial
     pub aux: Vec<E::G1>,
 }
@@ -57,6 +81,56 @@ pub struct KZH2Opening<E: Pairing> {
     pub f_star: MultilinearPolynomial<E::ScalarField>,
 }
 
+/// Opening of a hiding commitment, f_star is masked by a random polynomial g so it leaks nothing
+/// beyond the evaluation itself
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct KZH2HidingOpening<E: Pairing> {
+    /// blinded row commitments
+    pub D_x: Vec<E::G1Affine>,
+    /// commitment to the masking polynomial g w.r.t. H_y and H_y_blind
+    pub mask_com: E::G1Affine,
+    /// evaluation of the masking polynomial g at the y part of the input
+    pub mask_eval: E::ScalarField,
+    /// f_star + c * g for the challenge c
+    pub f_star: MultilinearPolynomial<E::ScalarField>,
+    /// the blinder of f_star + c * g w.r.t. H_y_blind
+    pub blinder: E::ScalarField,
+}
+
+/// The part of the SRS needed for verification, with the G2 elements prepared for use in pairings
+/// so that checking many proofs against the same SRS does not re-prepare them every time
+#[derive(Clone, Debug)]
+pub struct KZH2PreparedVerifierKey<E: Pairing> {
+    pub degree_x: usize,
+    pub degree_y: usize,
+    pub H_y: Vec<E::G1Affine>,
+    /// V_x, prepared for use in pairings
+    pub prepared_V_x: Vec<E::G2Prepared>,
+    /// V_prime, prepared for use in pairings
+    pub prepared_V_prime: E::G2Prepared,
+}
+
+impl<E: Pairing> KZH2PreparedVerifierKey<E> {
+    /// prepare `KZH2PreparedVerifierKey` from `KZH2SRS`
+    pub fn prepare(srs: &KZH2SRS<E>) -> Self {
+        KZH2PreparedVerifierKey {
+            degree_x: srs.degree_x,
+            degree_y: srs.degree_y,
+            H_y: srs.H_y.clone(),
+            prepared_V_x: srs.V_x.iter().map(|v| v.into()).collect(),
+            prepared_V_prime: (&srs.V_prime).into(),
+        }
+    }
+}
+
+impl<E: Pairing> KZH2SRS<E> {
+    /// Returns the prepared verifier key of the SRS, it is prepared on the first call and reused by
+    /// every later verification against the SRS
+    pub fn prepared_verifier_key(&self) -> &KZH2PreparedVerifierKey<E> {
+        self.prepared.get_or_init(|| KZH2PreparedVerifierKey::prepare(self))
+    }
+}
+
 /// Define the new struct that encapsulates the functionality of polynomial commitment
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
 pub struct KZH2<E: Pairing> {
@@ -88,6 +162,7 @@ where
     type Degree = (usize, usize);
     type SRS = KZH2SRS<E>;
     type Commitment = KZH2Commitment<E>;
+    type CommitmentHint = KZH2CommitmentHint<E>;
     type Opening = KZH2Opening<E>;
 
     /// the function receives an input r and splits into two sub-vectors x and y to be used for PCS
@@ -119,8 +194,8 @@ where
         }
     }
 
-    fn setup<R: Rng>(maximum_degree: usize, rng: &mut R) -> Self::SRS {
-        let (degree_x, degree_y): (usize, usize) = Self::get_degree_from_maximum_supported_degree(maximum_degree);
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS {
+        let (degree_x, degree_y): (usize, usize) = degrees;
 
         let degree_x = 1 << degree_x;
         let degree_y = 1 << degree_y;
@@ -136,6 +211,9 @@ where
 
         let alpha = E::ScalarField::rand(rng);
 
+        // sample H, the blinding generator for hiding commitments
+        let G1_blinding_generator = E::G1Affine::rand(rng);
+
         // generate matrix_H
         let matrix_H: Vec<Vec<_>> = (0..degree_x).into_par_iter()
             .map(|i| {
@@ -153,6 +231,10 @@ where
         // generate V_prime
         let V_prime = G2_generator.mul(alpha);
 
+        // generate the blinding bases
+        let H_x_blind: Vec<_> = (0..degree_x).map(|i| G1_blinding_generator.mul(tau[i]).into()).collect();
+        let H_y_blind = G1_blinding_generator.mul(alpha).into();
+
         // return the output
         KZH2SRS {
             degree_x,
@@ -161,17 +243,21 @@ where
             H_y: vec_H,
             V_x: vec_V,
             V_prime,
+            H_x_blind,
+            H_y_blind,
+            prepared: SRSCache::default(),
         }
     }
 
-    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Commitment {
+    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> (Self::Commitment, Self::CommitmentHint) {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2();
+        assert!(poly.num_variables <= len, "polynomial has more variables than the srs supports");
         let poly = poly.extend_number_of_variables(len);
         assert_eq!(poly.num_variables, len);
         assert_eq!(poly.len, 1 << poly.num_variables);
         assert_eq!(poly.evaluation_over_boolean_hypercube.len(), poly.len);
 
-        KZH2Commitment {
+        let com = KZH2Commitment {
             C: {
                 // Collect all points and scalars into single vectors
                 let mut base = Vec::new();
@@ -186,6 +272,9 @@ where
 
                 E::G1::msm_unchecked(&base, &scalar).into_affine()
             },
+        };
+
+        let hint = KZH2CommitmentHint {
             aux: (0..srs.degree_x)
                 .into_par_iter() // Parallelize the D^{(x)} computation
                 .map(|i| {
@@ -195,13 +284,15 @@ where
                     )
                 })
                 .collect::<Vec<_>>(),
-        }
+        };
+
+        (com, hint)
     }
 
     fn open(
         srs: &Self::SRS,
         input: &[E::ScalarField],
-        com: &Self::Commitment,
+        hint: &Self::CommitmentHint,
         poly: &MultilinearPolynomial<E::ScalarField>,
     ) -> Self::Opening {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2();
@@ -213,26 +304,114 @@ where
         let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
 
         KZH2Opening {
-            D_x: com.aux.clone().into_iter().map(|g| g.into()).collect(),
+            D_x: hint.aux.iter().map(|g| (*g).into()).collect(),
             f_star: poly.partial_evaluation(split_input[0].as_slice()),
         }
     }
 
-    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) {
-        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+    /// The verifier key is prepared on the first verification against the SRS and cached in it, see
+    /// `KZH2SRS::prepared_verifier_key`
+    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) -> Result<(), PCSError> {
+        Self::verify_prepared(srs.prepared_verifier_key(), input, output, com, open)
+    }
+
+    /// At a boolean point the partial evaluation f_star is just the row of the polynomial, so the
+    /// opening is a slice of the evaluations and needs no field operations
+    fn open_boolean(
+        srs: &Self::SRS,
+        index: usize,
+        hint: &Self::CommitmentHint,
+        poly: &MultilinearPolynomial<E::ScalarField>,
+    ) -> Self::Opening {
+        assert!(index < srs.degree_x * srs.degree_y, "index out of range for the srs");
+
+        // the row containing the index, with the same zero padding as in commit
+        let start = (index / srs.degree_y) * srs.degree_y;
+        let evaluations = &poly.evaluation_over_boolean_hypercube;
+        let mut row = vec![E::ScalarField::ZERO; srs.degree_y];
+        if start < evaluations.len() {
+            let end = evaluations.len().min(start + srs.degree_y);
+            row[..end - start].copy_from_slice(&evaluations[start..end]);
+        }
+
+        KZH2Opening {
+            D_x: E::G1::normalize_batch(hint.aux.as_slice()),
+            f_star: MultilinearPolynomial::new(row),
+        }
+    }
+
+    /// At a boolean point the row commitment D_x[i] is checked directly against f_star instead of the
+    /// combination of all the row commitments, and the output is read off f_star
+    fn verify_boolean(
+        srs: &Self::SRS,
+        index: usize,
+        output: &E::ScalarField,
+        com: &Self::Commitment,
+        open: &Self::Opening,
+    ) -> Result<(), PCSError> {
+        let pvk = srs.prepared_verifier_key();
+        if index >= pvk.degree_x * pvk.degree_y {
+            return Err(PCSError::IndexOutOfRange);
+        }
+        Self::verify_pairing(pvk, com, open.D_x.as_slice())?;
+
+        if open.f_star.evaluation_over_boolean_hypercube.len() != pvk.degree_y {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+        let row_com = E::G1::msm_unchecked(pvk.H_y.as_slice(), open.f_star.evaluation_over_boolean_hypercube.as_slice());
+        if row_com != open.D_x[index / pvk.degree_y] {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        if open.f_star.evaluation_over_boolean_hypercube[index % pvk.degree_y] != *output {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> KZH2<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Checks e(C, V') = sum_i e(D_i, V_i) which binds the row commitments D_x to C, there must be
+    /// exactly one row commitment per row since the multi-pairing would silently ignore the others
+    fn verify_pairing(pvk: &KZH2PreparedVerifierKey<E>, com: &KZH2Commitment<E>, D_x: &[E::G1Affine]) -> Result<(), PCSError> {
+        if D_x.len() != pvk.degree_x {
+            return Err(PCSError::PairingCheckFailed);
+        }
 
-        // Step 1: pairing check
         // Combine the pairings into a single multi-pairing
         let g1_elems: Vec<_> = std::iter::once(com.C.clone())
-            .chain(open.D_x.iter().map(|g1| (E::G1Affine::zero() - g1).into()))
+            .chain(D_x.iter().map(|g1| (E::G1Affine::zero() - g1).into()))
             .collect();
 
-        let g2_elems: Vec<_> = std::iter::once(srs.V_prime.clone())
-            .chain(srs.V_x.iter().cloned())
+        let g2_elems: Vec<_> = std::iter::once(pvk.prepared_V_prime.clone())
+            .chain(pvk.prepared_V_x.iter().cloned())
             .collect();
 
         // Perform the combined pairing check
-        E::multi_pairing(&g1_elems, &g2_elems).check().unwrap();
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Same as `verify` but against a prepared verifier key
+    pub fn verify_prepared(
+        pvk: &KZH2PreparedVerifierKey<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH2Commitment<E>,
+        open: &KZH2Opening<E>,
+    ) -> Result<(), PCSError> {
+        let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y], input, E::ScalarField::ZERO);
+
+        // Step 1: pairing check
+        Self::verify_pairing(pvk, com, open.D_x.as_slice())?;
 
         // Step 2: MSM check
         let negated_eq_evals: Vec<_> = EqPolynomial::new(split_input[0].clone())
@@ -247,33 +426,535 @@ where
             .cloned()
             .collect();
 
-        let bases: Vec<_> = srs.H_y.iter()
+        let bases: Vec<_> = pvk.H_y.iter()
             .chain(open.D_x.iter())
             .cloned()
             .collect();
 
-        assert!(E::G1::msm_unchecked(&bases, &scalars).is_zero());
+        if !E::G1::msm_unchecked(&bases, &scalars).is_zero() {
+            return Err(PCSError::MSMCheckFailed);
+        }
 
         // Step 3: complete poly eval
+        if open.f_star.num_variables != split_input[1].len() {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
         let y_expected = open.f_star.evaluate(split_input[1].as_slice());
-        assert_eq!(y_expected, *output);
+        if y_expected != *output {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies many independent openings at once, in the spirit of `KZG10::batch_check`: the pairing
+    /// equations and the MSM checks of all the openings are combined with random scalars into a single
+    /// multi-pairing and a single MSM. A failure doesn't tell which opening is invalid, use
+    /// `batch_verify_with_bisection` for that
+    pub fn batch_verify<R: Rng>(
+        pvk: &KZH2PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH2Commitment<E>],
+        opens: &[KZH2Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        if inputs.len() != coms.len() || outputs.len() != coms.len() || opens.len() != coms.len() {
+            return Err(PCSError::InvalidBatchSize);
+        }
+
+        let indices: Vec<usize> = (0..coms.len()).collect();
+        Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, indices.as_slice(), rng)
+    }
+
+    /// Same as `batch_verify` but when the batch fails it is bisected to find the invalid openings,
+    /// whose indices are returned. The indices missing an input, an output, a commitment or an
+    /// opening, when the slices have different lengths, are reported as invalid too
+    pub fn batch_verify_with_bisection<R: Rng>(
+        pvk: &KZH2PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH2Commitment<E>],
+        opens: &[KZH2Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>> {
+        let complete = inputs.len().min(outputs.len()).min(coms.len()).min(opens.len());
+        let total = inputs.len().max(outputs.len()).max(coms.len()).max(opens.len());
+
+        let indices: Vec<usize> = (0..complete).collect();
+        let mut invalid = bisect_failures(indices.as_slice(), &mut |subset| {
+            Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, subset, rng).is_ok()
+        });
+        invalid.extend(complete..total);
+
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+
+    /// Batch verifies the openings with the given indices, every slice must have an entry for each of them
+    fn batch_verify_subset<R: Rng>(
+        pvk: &KZH2PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH2Commitment<E>],
+        opens: &[KZH2Opening<E>],
+        indices: &[usize],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        // the evaluation checks only involve field operations, so they are done one by one
+        let mut eq_evals = Vec::with_capacity(indices.len());
+        for &k in indices {
+            let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y], inputs[k].as_slice(), E::ScalarField::ZERO);
+            let open = &opens[k];
+
+            if open.D_x.len() != pvk.degree_x {
+                return Err(PCSError::PairingCheckFailed);
+            }
+            if open.f_star.num_variables != split_input[1].len()
+                || open.f_star.evaluate(split_input[1].as_slice()) != outputs[k] {
+                return Err(PCSError::EvaluationCheckFailed);
+            }
+
+            eq_evals.push(EqPolynomial::new(split_input[0].clone()).evals());
+        }
+
+        let randomizers: Vec<E::ScalarField> = get_batch_randomizers(indices.len(), rng);
+
+        // Step 1: sum_k r_k * (e(C_k, V') - sum_i e(D_x,k[i], V_x[i])) = 0 as a single multi-pairing
+        let C: Vec<_> = indices.iter().map(|&k| coms[k].C).collect();
+        let D_x: Vec<&[E::G1Affine]> = indices.iter().map(|&k| opens[k].D_x.as_slice()).collect();
+
+        let total_C = E::G1::msm_unchecked(C.as_slice(), randomizers.as_slice());
+        let total_D_x = combine_columns::<E::G1>(D_x.as_slice(), randomizers.as_slice(), pvk.degree_x);
+
+        let g1_elems = E::G1::normalize_batch(
+            std::iter::once(total_C)
+                .chain(total_D_x.into_iter().map(|d| -d))
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        let g2_elems: Vec<_> = std::iter::once(pvk.prepared_V_prime.clone())
+            .chain(pvk.prepared_V_x.iter().cloned())
+            .collect();
+
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        // Step 2: sum_k r_k * (MSM(H_y, f_star_k) - MSM(D_x,k, eq(x_k))) = 0 as a single MSM
+        let mut scalars = vec![E::ScalarField::ZERO; pvk.degree_y];
+        let mut bases = pvk.H_y.clone();
+        for ((&k, r), eq) in indices.iter().zip(randomizers.iter()).zip(eq_evals.iter()) {
+            for (scalar, f) in scalars[..pvk.degree_y].iter_mut().zip(opens[k].f_star.evaluation_over_boolean_hypercube.iter()) {
+                *scalar += *r * f;
+            }
+            scalars.extend(eq.iter().map(|e| -(*r * e)));
+            bases.extend_from_slice(opens[k].D_x.as_slice());
+        }
+
+        if !E::G1::msm_unchecked(bases.as_slice(), scalars.as_slice()).is_zero() {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Commits to the polynomial with random blinders s_i, one per row:
+    /// aux_i = D_i + s_i * H_y_blind and C = C + sum_i s_i * H_x_blind.
+    /// The blinders are returned since they are needed to open the commitment
+    pub fn commit_hiding<R: Rng>(
+        srs: &KZH2SRS<E>,
+        poly: &MultilinearPolynomial<E::ScalarField>,
+        rng: &mut R,
+    ) -> (KZH2Commitment<E>, KZH2CommitmentHint<E>, Vec<E::ScalarField>) {
+        let blinders: Vec<_> = (0..srs.degree_x).map(|_| E::ScalarField::rand(rng)).collect();
+
+        let (com, hint) = Self::commit(srs, poly);
+        let C = (com.C + E::G1::msm_unchecked(srs.H_x_blind.as_slice(), blinders.as_slice())).into_affine();
+        let aux = hint.aux.into_iter()
+            .zip(blinders.clone())
+            .map(|(d, s)| d + srs.H_y_blind.mul(s))
+            .collect();
+
+        (KZH2Commitment { C }, KZH2CommitmentHint { aux }, blinders)
+    }
+
+    /// Opens a commitment produced by commit_hiding. The prover samples a masking polynomial g and
+    /// sends its commitment and g(y), then on challenge c reveals f_star + c * g with the combined blinder
+    pub fn open_hiding<R: Rng>(
+        srs: &KZH2SRS<E>,
+        input: &[E::ScalarField],
+        com: &KZH2Commitment<E>,
+        hint: &KZH2CommitmentHint<E>,
+        poly: &MultilinearPolynomial<E::ScalarField>,
+        blinders: &[E::ScalarField],
+        transcript: &mut Transcript<E::ScalarField>,
+        rng: &mut R,
+    ) -> KZH2HidingOpening<E> {
+        assert_eq!(blinders.len(), srs.degree_x, "there should be one blinder per row");
+
+        let len = srs.degree_x.log_2() + srs.degree_y.log_2();
+        let poly = poly.extend_number_of_variables(len);
+        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+
+        let f_star = poly.partial_evaluation(split_input[0].as_slice());
+        let f_star_blinder: E::ScalarField = EqPolynomial::new(split_input[0].clone())
+            .evals()
+            .iter()
+            .zip(blinders.iter())
+            .map(|(eq, s)| *eq * s)
+            .sum();
+        let output = f_star.evaluate(split_input[1].as_slice());
+
+        // the masking polynomial and its commitment
+        let mut mask = MultilinearPolynomial::rand(split_input[1].len(), rng);
+        let mask_blinder = E::ScalarField::rand(rng);
+        let mask_com = (E::G1::msm_unchecked(srs.H_y.as_slice(), mask.evaluation_over_boolean_hypercube.as_slice())
+            + srs.H_y_blind.mul(mask_blinder)).into_affine();
+        let mask_eval = mask.evaluate(split_input[1].as_slice());
+
+        let D_x: Vec<E::G1Affine> = hint.aux.iter().map(|g| (*g).into()).collect();
+        let c = Self::get_hiding_challenge(transcript, input, &output, com, D_x.as_slice(), &mask_com, &mask_eval);
+
+        mask.scalar_mul(&c);
+
+        KZH2HidingOpening {
+            D_x,
+            mask_com,
+            mask_eval,
+            f_star: f_star + mask,
+            blinder: f_star_blinder + c * mask_blinder,
+        }
+    }
+
+    /// Verifies an opening produced by open_hiding
+    pub fn verify_hiding(
+        srs: &KZH2SRS<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH2Commitment<E>,
+        open: &KZH2HidingOpening<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Result<(), PCSError> {
+        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+
+        // Step 1: pairing check
+        Self::verify_pairing(srs.prepared_verifier_key(), com, open.D_x.as_slice())?;
+
+        let c = Self::get_hiding_challenge(transcript, input, output, com, open.D_x.as_slice(), &open.mask_com, &open.mask_eval);
+
+        // Step 2: MSM check, sum_j f_star[j] * H_y[j] + blinder * H_y_blind = sum_i eq(x, i) * D_i + c * mask_com
+        let negated_eq_evals: Vec<_> = EqPolynomial::new(split_input[0].clone())
+            .evals()
+            .into_iter()
+            .map(|scalar| -scalar)
+            .collect();
+
+        let scalars: Vec<_> = open.f_star.evaluation_over_boolean_hypercube
+            .iter()
+            .chain(negated_eq_evals.iter())
+            .cloned()
+            .chain([open.blinder, -c])
+            .collect();
+
+        let bases: Vec<_> = srs.H_y.iter()
+            .chain(open.D_x.iter())
+            .cloned()
+            .chain([srs.H_y_blind, open.mask_com])
+            .collect();
+
+        if scalars.len() != bases.len() || !E::G1::msm_unchecked(&bases, &scalars).is_zero() {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        // Step 3: complete poly eval, the masked polynomial evaluates to output + c * g(y)
+        if open.f_star.num_variables != split_input[1].len() {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        let y_expected = open.f_star.evaluate(split_input[1].as_slice());
+        if y_expected != *output + c * open.mask_eval {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Binds the statement and the prover's first message to the transcript and returns the challenge c
+    fn get_hiding_challenge(
+        transcript: &mut Transcript<E::ScalarField>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH2Commitment<E>,
+        D_x: &[E::G1Affine],
+        mask_com: &E::G1Affine,
+        mask_eval: &E::ScalarField,
+    ) -> E::ScalarField {
+        com.append_to_transcript(b"hiding commitment", transcript);
+        transcript.append_scalars(b"hiding point", input);
+        transcript.append_scalar(b"hiding evaluation", output);
+        transcript.append_points::<E>(b"hiding D_x", D_x);
+        transcript.append_point::<E>(b"hiding mask commitment", mask_com);
+        transcript.append_scalar(b"hiding mask evaluation", mask_eval);
+
+        transcript.challenge_scalar(b"hiding challenge")
+    }
+}
+
+impl<E: Pairing> KZH2<E> {
+    /// Reports the opening size and the verifier cost when the variables are split into (x, y) with
+    /// the given numbers of variables: the opening has 2^x row commitments and 2^y evaluations of f_star,
+    /// the verifier runs an MSM over both and a multi-pairing of size 2^x + 1
+    pub fn cost_report(degrees: (usize, usize)) -> CostReport {
+        let (degree_x, degree_y) = (1 << degrees.0, 1 << degrees.1);
+
+        CostReport::new::<E>(vec![degrees.0, degrees.1], degree_x, degree_y, degree_x + degree_y, degree_x + 1)
+    }
+
+    /// Reports the cost of every split of `num_variables` variables between x and y
+    pub fn cost_reports(num_variables: usize) -> Vec<CostReport> {
+        (0..=num_variables)
+            .map(|x| Self::cost_report((x, num_variables - x)))
+            .collect()
+    }
+}
+
+impl<E: Pairing> KZH2<E> {
+    /// Commits to a polynomial given as a stream of its rows f(0, .), f(1, .), ... so that the whole
+    /// polynomial is never kept in memory, the peak memory is a single row. Missing trailing rows and
+    /// missing trailing entries of a row are treated as zeros, the same way `commit` pads a polynomial
+    /// with fewer variables, so the result is identical to `commit`
+    pub fn commit_streaming<I, R>(srs: &KZH2SRS<E>, rows: I) -> (KZH2Commitment<E>, KZH2CommitmentHint<E>)
+    where
+        I: IntoIterator<Item=R>,
+        R: AsRef<[E::ScalarField]>,
+    {
+        let mut C = E::G1::ZERO;
+        let mut aux = Vec::with_capacity(srs.degree_x);
+
+        for row in rows {
+            let (row_com, row_aux) = Self::commit_row(srs, aux.len(), row.as_ref());
+            C += row_com;
+            aux.push(row_aux);
+        }
+        aux.resize(srs.degree_x, E::G1::ZERO);
+
+        (KZH2Commitment { C: C.into_affine() }, KZH2CommitmentHint { aux })
+    }
+
+    /// Commits to a polynomial with `num_variables` variables whose evaluations over the boolean
+    /// hypercube are read in order from `reader`, each one serialized uncompressed. The reader can be
+    /// a file or a memory-mapped buffer, only one row is deserialized at a time
+    pub fn commit_from_reader<R: Read>(
+        srs: &KZH2SRS<E>,
+        num_variables: usize,
+        mut reader: R,
+    ) -> Result<(KZH2Commitment<E>, KZH2CommitmentHint<E>), SerializationError> {
+        assert!(num_variables <= srs.degree_x.log_2() + srs.degree_y.log_2(), "polynomial has too many variables");
+
+        let mut C = E::G1::ZERO;
+        let mut aux = Vec::with_capacity(srs.degree_x);
+        let mut row = Vec::with_capacity(srs.degree_y);
+
+        let mut remaining = 1usize << num_variables;
+        while remaining > 0 {
+            row.clear();
+            for _ in 0..remaining.min(srs.degree_y) {
+                row.push(E::ScalarField::deserialize_uncompressed(&mut reader)?);
+            }
+            remaining -= row.len();
+
+            let (row_com, row_aux) = Self::commit_row(srs, aux.len(), row.as_slice());
+            C += row_com;
+            aux.push(row_aux);
+        }
+        aux.resize(srs.degree_x, E::G1::ZERO);
+
+        Ok((KZH2Commitment { C: C.into_affine() }, KZH2CommitmentHint { aux }))
+    }
+
+    /// Commits to the polynomial whose evaluations over the boolean hypercube are zero except at the
+    /// given (index, value) entries, repeated indices are summed. Rows without any entry are skipped
+    /// and entries equal to one are added to the result without a scalar multiplication, which makes
+    /// this much faster than `commit` for bitfields and padded witnesses. The result is identical to
+    /// the dense `commit`
+    pub fn commit_sparse(srs: &KZH2SRS<E>, entries: &[(usize, E::ScalarField)]) -> (KZH2Commitment<E>, KZH2CommitmentHint<E>) {
+        let mut C = E::G1::ZERO;
+        let mut aux = vec![E::G1::ZERO; srs.degree_x];
+        for (i, (row_com, row_aux)) in Self::commit_sparse_rows(srs, entries) {
+            C += row_com;
+            aux[i] = row_aux;
+        }
+
+        (KZH2Commitment { C: C.into_affine() }, KZH2CommitmentHint { aux })
+    }
+
+    /// Updates a commitment and its hint in place after the evaluations at the given indices changed
+    /// by the given deltas, i.e. after f[index] += delta for every (index, delta). Only the SRS elements
+    /// of the touched positions are used, so the cost is proportional to the number of changes
+    pub fn update_commitment(
+        srs: &KZH2SRS<E>,
+        com: &mut KZH2Commitment<E>,
+        hint: &mut KZH2CommitmentHint<E>,
+        deltas: &[(usize, E::ScalarField)],
+    ) {
+        assert_eq!(hint.aux.len(), srs.degree_x, "hint does not match the srs");
+
+        let mut C = com.C.into_group();
+        for (i, (row_com, row_aux)) in Self::commit_sparse_rows(srs, deltas) {
+            C += row_com;
+            hint.aux[i] += row_aux;
+        }
+        com.C = C.into_affine();
+    }
+
+    /// Same as `commit` but only touches the non-zero evaluations of the polynomial, see `commit_sparse`
+    pub fn commit_sparse_poly(srs: &KZH2SRS<E>, poly: &MultilinearPolynomial<E::ScalarField>) -> (KZH2Commitment<E>, KZH2CommitmentHint<E>) {
+        assert!(poly.num_variables <= srs.degree_x.log_2() + srs.degree_y.log_2(), "polynomial has too many variables");
+
+        let entries: Vec<_> = poly.evaluation_over_boolean_hypercube.iter()
+            .enumerate()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(index, value)| (index, *value))
+            .collect();
+
+        Self::commit_sparse(srs, entries.as_slice())
+    }
+
+    /// Groups the non-zero (index, value) entries by row and returns, for every row with at least one
+    /// entry, its index together with its contribution to C and its row commitment
+    fn commit_sparse_rows(srs: &KZH2SRS<E>, entries: &[(usize, E::ScalarField)]) -> Vec<(usize, (E::G1, E::G1))> {
+        let mut rows: BTreeMap<usize, Vec<(usize, E::ScalarField)>> = BTreeMap::new();
+        for (index, value) in entries {
+            assert!(*index < srs.degree_x * srs.degree_y, "index out of range for the srs");
+            if !value.is_zero() {
+                rows.entry(index / srs.degree_y).or_default().push((index % srs.degree_y, *value));
+            }
+        }
+
+        rows.into_iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(i, row)| (i, Self::commit_sparse_row(srs, i, row.as_slice())))
+            .collect()
+    }
+
+    /// Returns the contribution of the i-th row to C and the i-th row commitment aux[i] when the row is
+    /// given by its non-zero (column, value) entries
+    fn commit_sparse_row(srs: &KZH2SRS<E>, i: usize, row: &[(usize, E::ScalarField)]) -> (E::G1, E::G1) {
+        let mut row_com = E::G1::ZERO;
+        let mut row_aux = E::G1::ZERO;
+
+        // entries equal to one only need an addition, the rest go into an MSM
+        let mut bases_xy = Vec::new();
+        let mut bases_y = Vec::new();
+        let mut scalars = Vec::new();
+        for (j, value) in row {
+            if value.is_one() {
+                row_com += srs.H_xy[i][*j];
+                row_aux += srs.H_y[*j];
+            } else {
+                bases_xy.push(srs.H_xy[i][*j]);
+                bases_y.push(srs.H_y[*j]);
+                scalars.push(*value);
+            }
+        }
+
+        if !scalars.is_empty() {
+            row_com += E::G1::msm_unchecked(bases_xy.as_slice(), scalars.as_slice());
+            row_aux += E::G1::msm_unchecked(bases_y.as_slice(), scalars.as_slice());
+        }
+
+        (row_com, row_aux)
+    }
+
+    /// Returns the contribution of the i-th row to C and the i-th row commitment aux[i]
+    fn commit_row(srs: &KZH2SRS<E>, i: usize, row: &[E::ScalarField]) -> (E::G1, E::G1) {
+        assert!(i < srs.degree_x, "too many rows for the srs");
+        assert!(row.len() <= srs.degree_y, "row is longer than degree_y");
+
+        (
+            E::G1::msm_unchecked(&srs.H_xy[i][..row.len()], row),
+            E::G1::msm_unchecked(&srs.H_y[..row.len()], row),
+        )
+    }
+}
+
+impl<E: Pairing> KZH2SRS<E> {
+    /// Checks that H_xy[i][j] = tau_i * G_j and H_y[j] = alpha * G_j for the tau_i and alpha defined by
+    /// V_x[i] = tau_i * V and V_prime = alpha * V, and likewise for the blinding bases. All rows are
+    /// checked at once with a random linear combination, so the cost is a few MSMs and four pairings
+    pub fn check_well_formed<R: Rng>(&self, rng: &mut R) -> Result<(), SRSError> {
+        if self.H_xy.len() != self.degree_x
+            || self.H_xy.iter().any(|row| row.len() != self.degree_y)
+            || self.H_y.len() != self.degree_y
+            || self.V_x.len() != self.degree_x
+            || self.H_x_blind.len() != self.degree_x
+            || self.V_prime.is_zero() {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        let V_x = E::G2::normalize_batch(self.V_x.as_slice());
+        let V_prime = self.V_prime.into_affine();
+
+        if !check_level::<E, _>(self.H_xy.concat().as_slice(), self.H_y.as_slice(), V_x.as_slice(), &V_prime, rng)
+            || !check_level::<E, _>(self.H_x_blind.as_slice(), &[self.H_y_blind], V_x.as_slice(), &V_prime, rng) {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> KZH2SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the SRS for a smaller maximum degree, the variables are split evenly if the SRS is
+    /// large enough in both dimensions and otherwise as close to evenly as its shape allows, it fails
+    /// if the SRS supports fewer variables
+    pub fn trim(&self, maximum_degree: usize) -> Result<KZH2SRS<E>, SRSError> {
+        let (degree_x, degree_y) = KZH2::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        let degrees = trimmed_degrees(&[degree_x, degree_y], &[self.degree_x.log_2(), self.degree_y.log_2()], maximum_degree).ok_or(SRSError::TrimmedDegreeTooLarge)?;
+
+        self.trim_with_degrees((degrees[0], degrees[1]))
+    }
+
+    /// Derives the SRS for the given log degrees, since the trapdoors of different rows and
+    /// columns are independent it's enough to keep the first degree_x rows and degree_y columns
+    pub fn trim_with_degrees(&self, degrees: (usize, usize)) -> Result<KZH2SRS<E>, SRSError> {
+        let degree_x = trimmed_degree(degrees.0, self.degree_x)?;
+        let degree_y = trimmed_degree(degrees.1, self.degree_y)?;
+
+        Ok(KZH2SRS {
+            degree_x,
+            degree_y,
+            H_xy: self.H_xy[..degree_x].iter().map(|row| row[..degree_y].to_vec()).collect(),
+            H_y: self.H_y[..degree_y].to_vec(),
+            V_x: self.V_x[..degree_x].to_vec(),
+            V_prime: self.V_prime,
+            H_x_blind: self.H_x_blind[..degree_x].to_vec(),
+            H_y_blind: self.H_y_blind,
+            prepared: SRSCache::default(),
+        })
     }
 }
 
 impl<E: Pairing> KZH2Commitment<E> {
-    /// Scales the commitment and its auxiliary elements by a scalar `r`
+    /// Scales the commitment by a scalar `r`
     pub fn scale_by_r(&mut self, r: &E::ScalarField) {
-        // Scale the main commitment C by r
-        let scaled_C = self.C.mul(r); // G1Affine -> G1Projective when multiplied by scalar
+        self.C = self.C.mul(r).into_affine();
+    }
+}
 
+impl<E: Pairing> KZH2CommitmentHint<E> {
+    /// Scales the auxiliary elements by a scalar `r`
+    pub fn scale_by_r(&mut self, r: &E::ScalarField) {
         // Scale each element in the aux vector by r
-        let scaled_aux: Vec<E::G1> = self.aux.iter()
-            .map(|element| element.mul(r))  // Multiply each element in aux by r
+        self.aux = self.aux.iter()
+            .map(|element| element.mul(r))
             .collect();
-
-        // Update the commitment with the scaled values
-        self.C = scaled_C.into_affine();  // Convert back to G1Affine after multiplication
-        self.aux = scaled_aux;
     }
 }
 
@@ -282,11 +963,18 @@ impl<E: Pairing> Add for KZH2Commitment<E> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        // Ensure both commitments have the same size in aux vectors
-        assert_eq!(self.aux.len(), other.aux.len(), "Aux vectors must have the same length");
+        KZH2Commitment {
+            C: (self.C + other.C).into_affine(),
+        }
+    }
+}
 
-        // Add the main commitment points C
-        let C = (self.C + other.C).into_affine();
+impl<E: Pairing> Add for KZH2CommitmentHint<E> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        // Ensure both hints have the same size in aux vectors
+        assert_eq!(self.aux.len(), other.aux.len(), "Aux vectors must have the same length");
 
         // Add the corresponding elements in the aux vector
         let new_aux: Vec<E::G1> = self.aux.iter()
@@ -294,23 +982,78 @@ impl<E: Pairing> Add for KZH2Commitment<E> {
             .map(|(a, b)| *a + *b)
             .collect();
 
-        // Return a new Commitment with the resulting sums
-        KZH2Commitment {
-            C,
+        KZH2CommitmentHint {
             aux: new_aux,
         }
     }
 }
 
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH2Commitment<E> {
+    fn add(&self, other: &Self) -> Self {
+        self.clone() + other.clone()
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        let mut com = self.clone();
+        com.scale_by_r(r);
+        com
+    }
+
+    fn linear_combination(terms: &[(E::ScalarField, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        // accumulate in projective form to normalize only once
+        let C = terms.iter().fold(E::G1::ZERO, |acc, (coeff, com)| acc + com.C.mul(coeff));
+        KZH2Commitment { C: C.into_affine() }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH2CommitmentHint<E> {
+    fn add(&self, other: &Self) -> Self {
+        self.clone() + other.clone()
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        let mut hint = self.clone();
+        hint.scale_by_r(r);
+        hint
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH2Opening<E> {
+    fn add(&self, other: &Self) -> Self {
+        assert_eq!(self.D_x.len(), other.D_x.len(), "openings must have the same size");
+
+        let D_x: Vec<E::G1> = self.D_x.iter().zip(other.D_x.iter()).map(|(a, b)| *a + b).collect();
+        KZH2Opening {
+            D_x: E::G1::normalize_batch(D_x.as_slice()),
+            f_star: Homomorphic::add(&self.f_star, &other.f_star),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        let D_x: Vec<E::G1> = self.D_x.iter().map(|d| d.mul(r)).collect();
+        KZH2Opening {
+            D_x: E::G1::normalize_batch(D_x.as_slice()),
+            f_star: self.f_star.scale(r),
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod test {
+    use ark_ff::AdditiveGroup;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::UniformRand;
     use rand::thread_rng;
 
     use crate::constant_for_curves::{ScalarField as F, E};
-    use crate::kzh::kzh2::{KZH2, KZH2SRS};
-    use crate::kzh::KZH;
+    use crate::kzh::errors::{PCSError, SRSError};
+    use crate::kzh::kzh2::{KZH2PreparedVerifierKey, KZH2, KZH2SRS};
+    use crate::kzh::kzh3::{KZH3, KZH3SRS};
+    use crate::kzh::{boolean_point, KZH};
     use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use crate::transcript::transcript::Transcript;
 
     #[test]
     fn test_end_to_end() {
@@ -332,16 +1075,395 @@ pub mod test {
         let z = polynomial.evaluate(&input);
 
         // commit to the polynomial
-        let com = KZH2::commit(&srs, &polynomial);
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
+
+        // the commitment sent to the verifier is a single group element
+        assert_eq!(com.compressed_size(), com.C.compressed_size());
 
         // open the commitment
-        let open = KZH2::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
 
         // re compute x and y verify the proof
-        KZH2::verify(&srs, input.as_slice(), &z, &com, &open);
+        KZH2::verify(&srs, input.as_slice(), &z, &com, &open).unwrap();
+
+        // a wrong output must be rejected at the final evaluation check
+        assert_eq!(
+            KZH2::verify(&srs, input.as_slice(), &(z + F::from(1u64)), &com, &open),
+            Err(PCSError::EvaluationCheckFailed)
+        );
     }
 
 
+    #[test]
+    fn test_prepared_verifier_key() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        let pvk = KZH2PreparedVerifierKey::prepare(&srs);
+
+        // check several openings against the same prepared key
+        for _ in 0..3 {
+            let polynomial = MultilinearPolynomial::rand(8, &mut thread_rng());
+            let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+                .take(8)
+                .collect();
+            let z = polynomial.evaluate(&input);
+
+            let (com, hint) = KZH2::commit(&srs, &polynomial);
+            let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
+
+            KZH2::verify_prepared(&pvk, input.as_slice(), &z, &com, &open).unwrap();
+            assert!(KZH2::verify_prepared(&pvk, input.as_slice(), &(z + F::from(1u64)), &com, &open).is_err());
+        }
+
+        // the trailing row commitments of a small polynomial are zero, dropping them must be caught
+        let polynomial = MultilinearPolynomial::rand(5, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(8)
+            .collect();
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
+        let mut open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
+        open.D_x.truncate(2);
+        assert_eq!(
+            KZH2::verify_prepared(&pvk, input.as_slice(), &polynomial.extend_number_of_variables(8).evaluate(&input), &com, &open),
+            Err(PCSError::PairingCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_cached_verifier_key() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        let polynomial = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(8)
+            .collect();
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
+        let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
+
+        // the key is prepared by the first verification and reused by the next ones
+        KZH2::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+        let pvk = srs.prepared_verifier_key();
+        KZH2::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+        assert!(std::ptr::eq(pvk, srs.prepared_verifier_key()));
+
+        // it is not part of the serialized srs, nor of a modified copy of it
+        let mut bytes = Vec::new();
+        srs.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(KZH2SRS::<E>::deserialize_compressed(bytes.as_slice()).unwrap(), srs);
+
+        let mut other = srs.clone();
+        other.V_x.swap(0, 1);
+        assert!(KZH2::verify(&other, input.as_slice(), &polynomial.evaluate(&input), &com, &open).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        let pvk = KZH2PreparedVerifierKey::prepare(&srs);
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut coms = Vec::new();
+        let mut opens = Vec::new();
+        for _ in 0..5 {
+            let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(8, &mut thread_rng());
+            let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+            let (com, hint) = KZH2::commit(&srs, &polynomial);
+
+            opens.push(KZH2::open(&srs, input.as_slice(), &hint, &polynomial));
+            outputs.push(polynomial.evaluate(input.as_slice()));
+            inputs.push(input);
+            coms.push(com);
+        }
+
+        KZH2::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).unwrap();
+
+        // a wrong evaluation and a tampered opening are caught, bisection tells which ones
+        outputs[3] += F::from(1u64);
+        opens[1].D_x.swap(0, 1);
+        assert!(KZH2::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).is_err());
+        assert_eq!(
+            KZH2::batch_verify_with_bisection(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()),
+            Err(vec![1, 3])
+        );
+
+        // a missing opening is reported instead of panicking
+        assert_eq!(
+            KZH2::batch_verify(&pvk, &inputs, &outputs, &coms, &opens[..4], &mut thread_rng()),
+            Err(PCSError::InvalidBatchSize)
+        );
+        assert_eq!(
+            KZH2::batch_verify_with_bisection(&pvk, &inputs, &outputs, &coms, &opens[..4], &mut thread_rng()),
+            Err(vec![1, 3, 4])
+        );
+    }
+
+    #[test]
+    fn test_open_boolean() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+
+        for num_variables in [8, 5] {
+            let polynomial = MultilinearPolynomial::rand(num_variables, &mut thread_rng());
+            let (com, hint) = KZH2::commit(&srs, &polynomial);
+
+            for index in [0, 13, (1 << num_variables) - 1] {
+                let output = polynomial.evaluation_over_boolean_hypercube[index];
+                let open = KZH2::open_boolean(&srs, index, &hint, &polynomial);
+                KZH2::verify_boolean(&srs, index, &output, &com, &open).unwrap();
+
+                // it is a regular opening at the boolean point too
+                let input = boolean_point(index, 8);
+                assert_eq!(polynomial.evaluate(&input[8 - num_variables..]), output);
+                KZH2::verify(&srs, input.as_slice(), &output, &com, &open).unwrap();
+
+                assert_eq!(
+                    KZH2::verify_boolean(&srs, index, &(output + F::from(1u64)), &com, &open),
+                    Err(PCSError::EvaluationCheckFailed)
+                );
+                assert!(KZH2::verify_boolean(&srs, index ^ 16, &output, &com, &open).is_err());
+            }
+        }
+
+        // an index beyond the srs is an error, for KZH2 and for the default implementation
+        let polynomial = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
+        let open = KZH2::open_boolean(&srs, 0, &hint, &polynomial);
+        assert_eq!(KZH2::verify_boolean(&srs, 1 << 8, &F::ZERO, &com, &open), Err(PCSError::IndexOutOfRange));
+
+        let srs: KZH3SRS<E> = KZH3::setup(6, &mut thread_rng());
+        let polynomial = MultilinearPolynomial::rand(6, &mut thread_rng());
+        let (com, hint) = KZH3::commit(&srs, &polynomial);
+        let open = KZH3::open_boolean(&srs, 0, &hint, &polynomial);
+        assert_eq!(KZH3::verify_boolean(&srs, 1 << 6, &F::ZERO, &com, &open), Err(PCSError::IndexOutOfRange));
+    }
+
+    #[test]
+    fn test_hiding_end_to_end() {
+        let num_vars = 8;
+        let srs: KZH2SRS<E> = KZH2::setup(num_vars, &mut thread_rng());
+
+        let polynomial = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(num_vars)
+            .collect();
+        let z = polynomial.evaluate(&input);
+
+        // the hiding commitment differs from the deterministic one
+        let (com, hint, blinders) = KZH2::commit_hiding(&srs, &polynomial, &mut thread_rng());
+        assert_ne!(com, KZH2::commit(&srs, &polynomial).0);
+
+        let mut prover_transcript = Transcript::new(b"hiding");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let open = KZH2::open_hiding(&srs, input.as_slice(), &com, &hint, &polynomial, blinders.as_slice(), &mut prover_transcript, &mut thread_rng());
+        KZH2::verify_hiding(&srs, input.as_slice(), &z, &com, &open, &mut verifier_transcript).unwrap();
+
+        // the masked partial evaluation is not the real one
+        let split_input = KZH2::split_input(&srs, input.as_slice(), F::from(0u64));
+        assert_ne!(open.f_star, polynomial.partial_evaluation(split_input[0].as_slice()));
+
+        // a wrong output must be rejected
+        assert!(KZH2::verify_hiding(&srs, input.as_slice(), &(z + F::from(1u64)), &com, &open, &mut Transcript::new(b"hiding")).is_err());
+    }
+
+    #[test]
+    fn test_check_well_formed() {
+        let mut srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        // a single wrong element of H_xy is detected
+        srs.H_xy[2][5] = srs.H_xy[2][6];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+
+        // and so is a wrong element of H_y
+        let mut srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        srs.H_y[3] = srs.H_y[0];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+    }
+
+    #[test]
+    fn test_trim() {
+        let srs: KZH2SRS<E> = KZH2::setup(11, &mut thread_rng());
+        let trimmed = srs.trim(6).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y), (8, 8));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+
+        // trimming to more variables than the srs supports is an error
+        assert!(matches!(srs.trim(12), Err(SRSError::TrimmedDegreeTooLarge)));
+
+        let polynomial = MultilinearPolynomial::rand(6, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(6)
+            .collect();
+
+        let (com, hint) = KZH2::commit(&trimmed, &polynomial);
+        let open = KZH2::open(&trimmed, input.as_slice(), &hint, &polynomial);
+        KZH2::verify(&trimmed, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_trim_unbalanced() {
+        // the even split (3, 3) does not fit in 4 rows, so the columns are trimmed instead
+        let srs: KZH2SRS<E> = KZH2::setup_with_degrees((2, 8), &mut thread_rng());
+        let trimmed = srs.trim(6).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y), (4, 16));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+
+        let polynomial = MultilinearPolynomial::rand(6, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(6)
+            .collect();
+
+        let (com, hint) = KZH2::commit(&trimmed, &polynomial);
+        let open = KZH2::open(&trimmed, input.as_slice(), &hint, &polynomial);
+        KZH2::verify(&trimmed, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+
+        // explicit degrees are checked against the shape of the srs
+        let trimmed = srs.trim_with_degrees((1, 5)).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y), (2, 32));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+        assert!(matches!(srs.trim_with_degrees((3, 3)), Err(SRSError::TrimmedDegreeTooLarge)));
+        assert!(matches!(srs.trim_with_degrees((2, 64)), Err(SRSError::TrimmedDegreeTooLarge)));
+
+        // neither entry point panics on an srs which is too small
+        assert!(matches!(srs.trim(11), Err(SRSError::TrimmedDegreeTooLarge)));
+        assert!(matches!(srs.trim(usize::MAX), Err(SRSError::TrimmedDegreeTooLarge)));
+    }
+
+    #[test]
+    fn test_setup_with_degrees() {
+        // an unbalanced split with few rows
+        let srs: KZH2SRS<E> = KZH2::setup_with_degrees((2, 6), &mut thread_rng());
+        assert_eq!((srs.degree_x, srs.degree_y), (4, 64));
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        let polynomial = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(8)
+            .collect();
+
+        let (com, hint) = KZH2::commit(&srs, &polynomial);
+        let open = KZH2::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZH2::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+
+        // the report matches the actual opening
+        let report = KZH2::<E>::cost_report((2, 6));
+        assert_eq!(report.proof_group_elements, open.D_x.len());
+        assert_eq!(report.proof_field_elements, open.f_star.evaluation_over_boolean_hypercube.len());
+        assert_eq!(report.verifier_pairings, 5);
+
+        let reports = KZH2::<E>::cost_reports(8);
+        assert_eq!(reports.len(), 9);
+        assert_eq!(reports[2], report);
+    }
+
+    #[test]
+    fn test_commit_streaming() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+
+        for num_variables in [8, 6, 3] {
+            let polynomial = MultilinearPolynomial::rand(num_variables, &mut thread_rng());
+            let expected = KZH2::commit(&srs, &polynomial);
+
+            // feed the polynomial row by row
+            let rows = polynomial.evaluation_over_boolean_hypercube.chunks(srs.degree_y);
+            assert_eq!(KZH2::commit_streaming(&srs, rows), expected);
+
+            // read the polynomial from a serialized buffer
+            let mut bytes = Vec::new();
+            for e in polynomial.evaluation_over_boolean_hypercube.iter() {
+                e.serialize_uncompressed(&mut bytes).unwrap();
+            }
+            assert_eq!(KZH2::commit_from_reader(&srs, num_variables, bytes.as_slice()).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_commit_sparse() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+
+        // a bitfield, and a padded polynomial with a few arbitrary values
+        let bitfield = MultilinearPolynomial::random_binary(8, &mut thread_rng());
+        let padded = MultilinearPolynomial::rand(5, &mut thread_rng()).extend_number_of_variables(8);
+
+        for polynomial in [bitfield, padded] {
+            let (com, hint) = KZH2::commit(&srs, &polynomial);
+            let (sparse_com, sparse_hint) = KZH2::commit_sparse_poly(&srs, &polynomial);
+
+            let (mut bytes, mut sparse_bytes) = (Vec::new(), Vec::new());
+            (com, hint).serialize_compressed(&mut bytes).unwrap();
+            (sparse_com, sparse_hint).serialize_compressed(&mut sparse_bytes).unwrap();
+            assert_eq!(bytes, sparse_bytes);
+        }
+
+        // repeated indices are summed and zeros are ignored
+        let entries = vec![(3, F::from(2u64)), (3, F::from(5u64)), (200, F::from(1u64)), (17, F::from(0u64))];
+        let mut evaluations = vec![F::from(0u64); 256];
+        evaluations[3] = F::from(7u64);
+        evaluations[200] = F::from(1u64);
+        let polynomial = MultilinearPolynomial::new(evaluations);
+        assert_eq!(KZH2::commit_sparse(&srs, entries.as_slice()), KZH2::commit(&srs, &polynomial));
+    }
+
+    #[test]
+    fn test_update_commitment() {
+        let srs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+
+        let mut polynomial = MultilinearPolynomial::random_binary(8, &mut thread_rng());
+        let (mut com, mut hint) = KZH2::commit(&srs, &polynomial);
+
+        // flip a few bits and change one entry arbitrarily
+        let mut deltas = Vec::new();
+        for index in [0, 17, 18, 255] {
+            let delta = F::from(1u64) - polynomial.evaluation_over_boolean_hypercube[index].double();
+            polynomial.evaluation_over_boolean_hypercube[index] += delta;
+            deltas.push((index, delta));
+        }
+        let delta = F::rand(&mut thread_rng());
+        polynomial.evaluation_over_boolean_hypercube[100] += delta;
+        deltas.push((100, delta));
+
+        KZH2::update_commitment(&srs, &mut com, &mut hint, deltas.as_slice());
+        assert_eq!((com, hint), KZH2::commit(&srs, &polynomial));
+    }
+
+    #[test]
+    fn test_batch_open() {
+        let num_vars = 8;
+        let srs: KZH2SRS<E> = KZH2::setup(num_vars, &mut thread_rng());
+
+        let polys: Vec<MultilinearPolynomial<F>> = vec![
+            MultilinearPolynomial::rand(num_vars, &mut thread_rng()),
+            MultilinearPolynomial::rand(num_vars, &mut thread_rng()),
+            MultilinearPolynomial::rand(num_vars, &mut thread_rng()),
+        ];
+        let (coms, hints): (Vec<_>, Vec<_>) = polys.iter().map(|poly| KZH2::commit(&srs, poly)).unzip();
+
+        let input: Vec<_> = std::iter::repeat_with(|| F::rand(&mut thread_rng()))
+            .take(num_vars)
+            .collect();
+        let outputs: Vec<_> = polys.iter().map(|poly| poly.evaluate(&input)).collect();
+
+        let mut prover_transcript = Transcript::new(b"batch");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let open = KZH2::batch_open(&srs, input.as_slice(), coms.as_slice(), hints.as_slice(), polys.as_slice(), &mut prover_transcript);
+        KZH2::verify_batch_open(&srs, input.as_slice(), outputs.as_slice(), coms.as_slice(), &open, &mut verifier_transcript).unwrap();
+
+        // tampering with a single evaluation must be caught
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[1] += F::from(1u64);
+        assert!(KZH2::verify_batch_open(&srs, input.as_slice(), wrong_outputs.as_slice(), coms.as_slice(), &open, &mut Transcript::new(b"batch")).is_err());
+
+        // malformed batches are rejected instead of panicking
+        assert_eq!(
+            KZH2::verify_batch_open(&srs, input.as_slice(), &outputs[..2], coms.as_slice(), &open, &mut Transcript::new(b"batch")),
+            Err(PCSError::InvalidBatchSize)
+        );
+        assert_eq!(
+            KZH2::verify_batch_open(&srs, input.as_slice(), &[], &[], &open, &mut Transcript::new(b"batch")),
+            Err(PCSError::InvalidBatchSize)
+        );
+    }
+
     /// Given f(x) and g(x) and their KZH commitments F and G.
     /// This test computes p(x) = f(x) + r * g(x),
     /// and checks that its commitment is P = F + r*G
@@ -360,8 +1482,8 @@ pub mod test {
         let f_x: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
         let g_x: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
 
-        let F = KZH2::commit(&srs, &f_x);
-        let G = KZH2::commit(&srs, &g_x);
+        let (F, _) = KZH2::commit(&srs, &f_x);
+        let (G, _) = KZH2::commit(&srs, &g_x);
 
         // Verifier's challenge: for poly batching
         let r = F::rand(&mut thread_rng());
@@ -372,10 +1494,10 @@ pub mod test {
         let mut r_times_g_x = g_x.clone();
         r_times_g_x.scalar_mul(&r);
         let p_x = f_x.clone() + r_times_g_x;
-        let P = KZH2::commit(&srs, &p_x);
+        let (_, P_hint) = KZH2::commit(&srs, &p_x);
 
         // Open p_x at rho
-        let proof_P_at_rho = KZH2::open(&srs, rho.as_slice(), &P, &p_x);
+        let proof_P_at_rho = KZH2::open(&srs, rho.as_slice(), &P_hint, &p_x);
         let p_at_rho = p_x.evaluate(&rho);
 
         // Verifier:
@@ -386,7 +1508,7 @@ pub mod test {
         r_times_G.scale_by_r(&r);
         let P_verifier = F + r_times_G;
 
-        KZH2::verify(&srs, rho.as_slice(), &p_at_rho, &P_verifier, &proof_P_at_rho);
+        KZH2::verify(&srs, rho.as_slice(), &p_at_rho, &P_verifier, &proof_P_at_rho).unwrap();
     }
 }
 
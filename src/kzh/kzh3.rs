@@ -1,4 +1,5 @@
-use crate::kzh::KZH;
+use crate::kzh::errors::{PCSError, SRSError};
+use crate::kzh::{add_vectors, bisect_failures, check_level, check_proportional, combine_columns, get_batch_randomizers, scale_vector, split_input_by_degrees, trimmed_degree, trimmed_degrees, CostReport, Homomorphic, SRSCache, KZH};
 use crate::math::Math;
 use crate::nexus_spartan::commitment_traits::ToAffine;
 use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
@@ -7,7 +8,7 @@ use crate::transcript::transcript::{AppendToTranscript, Transcript};
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
-use ark_ff::{AdditiveGroup, PrimeField};
+use ark_ff::{AdditiveGroup, One, PrimeField, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
 use derivative::Derivative;
@@ -38,10 +39,13 @@ pub struct KZH3SRS<E: Pairing> {
     pub V_y: Vec<E::G2Affine>,
     pub V_z: Vec<E::G2Affine>,
     pub v: E::G2Affine,
+    /// the prepared verifier key, computed on the first verification, see `prepared_verifier_key`
+    pub prepared: SRSCache<KZH3PreparedVerifierKey<E>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
 pub struct KZH3Opening<E: Pairing> {
+    pub D_x: Vec<E::G1>,
     pub D_y: Vec<E::G1>,
     pub C_y: E::G1Affine,
     pub f_star: MultilinearPolynomial<E::ScalarField>,
@@ -58,10 +62,62 @@ pub struct KZH3Opening<E: Pairing> {
     Derivative
 )]
 pub struct KZH3Commitment<E: Pairing> {
-    pub D_x: Vec<E::G1>,
     pub C: E::G1Affine,
 }
 
+/// Prover-only data of a commitment, kept by the prover to open it and never sent to the verifier
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct KZH3CommitmentHint<E: Pairing> {
+    pub D_x: Vec<E::G1>,
+}
+
+/// The part of the SRS needed for verification, with the G2 elements prepared for use in pairings
+#[derive(Clone, Debug)]
+pub struct KZH3PreparedVerifierKey<E: Pairing> {
+    pub degree_x: usize,
+    pub degree_y: usize,
+    pub degree_z: usize,
+    pub H_z: Vec<E::G1Affine>,
+    /// V_x, prepared for use in pairings
+    pub prepared_V_x: Vec<E::G2Prepared>,
+    /// V_y, prepared for use in pairings
+    pub prepared_V_y: Vec<E::G2Prepared>,
+    /// v, prepared for use in pairings
+    pub prepared_v: E::G2Prepared,
+}
+
+impl<E: Pairing> KZH3PreparedVerifierKey<E> {
+    /// prepare `KZH3PreparedVerifierKey` from `KZH3SRS`
+    pub fn prepare(srs: &KZH3SRS<E>) -> Self {
+        KZH3PreparedVerifierKey {
+            degree_x: srs.degree_x,
+            degree_y: srs.degree_y,
+            degree_z: srs.degree_z,
+            H_z: srs.H_z.clone(),
+            prepared_V_x: srs.V_x.iter().map(|v| v.into()).collect(),
+            prepared_V_y: srs.V_y.iter().map(|v| v.into()).collect(),
+            prepared_v: srs.v.into(),
+        }
+    }
+}
+
+impl<E: Pairing> KZH3SRS<E> {
+    /// Returns the prepared verifier key of the SRS, it is prepared on the first call and reused by
+    /// every later verification against the SRS
+    pub fn prepared_verifier_key(&self) -> &KZH3PreparedVerifierKey<E> {
+        self.prepared.get_or_init(|| KZH3PreparedVerifierKey::prepare(self))
+    }
+}
+
 impl<E: Pairing> KZH<E> for KZH3<E>
 where
     <E as Pairing>::ScalarField: Absorb,
@@ -70,6 +126,7 @@ where
     type Degree = (usize, usize, usize);
     type SRS = KZH3SRS<E>;
     type Commitment = KZH3Commitment<E>;
+    type CommitmentHint = KZH3CommitmentHint<E>;
     type Opening = KZH3Opening<E>;
 
     fn split_input<T: Clone>(srs: &Self::SRS, input: &[T], default: T) -> Vec<Vec<T>> {
@@ -100,8 +157,8 @@ where
         }
     }
 
-    fn setup<R: Rng>(maximum_degree: usize, rng: &mut R) -> Self::SRS {
-        let (degree_x, degree_y, degree_z) = Self::get_degree_from_maximum_supported_degree(maximum_degree);
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS {
+        let (degree_x, degree_y, degree_z) = degrees;
 
         let (degree_x, degree_y, degree_z) = (1 << degree_x, 1 << degree_y, 1 << degree_z);
 
@@ -158,17 +215,19 @@ where
             V_y,
             V_z,
             v,
+            prepared: SRSCache::default(),
         }
     }
 
-    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Commitment {
+    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> (Self::Commitment, Self::CommitmentHint) {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2() + srs.degree_z.log_2();
+        assert!(poly.num_variables <= len, "polynomial has more variables than the srs supports");
         let poly = poly.extend_number_of_variables(len);
         assert_eq!(poly.num_variables, len);
         assert_eq!(poly.len, 1 << poly.num_variables);
         assert_eq!(poly.evaluation_over_boolean_hypercube.len(), poly.len);
 
-        KZH3Commitment {
+        let hint = KZH3CommitmentHint {
             D_x: (0..srs.degree_x)
                 .into_par_iter()
                 .map(|i| {
@@ -178,11 +237,16 @@ where
                     )
                 })
                 .collect::<Vec<_>>(),
+        };
+
+        let com = KZH3Commitment {
             C: E::G1::msm(&srs.H_xyz, &poly.evaluation_over_boolean_hypercube).unwrap().into(),
-        }
+        };
+
+        (com, hint)
     }
 
-    fn open(srs: &Self::SRS, input: &[E::ScalarField], com: &Self::Commitment, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Opening {
+    fn open(srs: &Self::SRS, input: &[E::ScalarField], hint: &Self::CommitmentHint, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Opening {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2() + srs.degree_z.log_2();
         let poly = poly.extend_number_of_variables(len);
         assert_eq!(poly.num_variables, len);
@@ -218,55 +282,330 @@ where
         }.as_slice());
 
         let C_y = E::G1::msm(
-            &com.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+            &hint.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
             EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
         ).unwrap().into();
 
         KZH3Opening {
+            D_x: hint.D_x.clone(),
             D_y,
             C_y,
             f_star,
         }
     }
 
-    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) {
-        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+    /// The verifier key is prepared on the first verification against the SRS and cached in it, see
+    /// `KZH3SRS::prepared_verifier_key`
+    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) -> Result<(), PCSError> {
+        Self::verify_prepared(srs.prepared_verifier_key(), input, output, com, open)
+    }
+}
+
+impl<E: Pairing> KZH3<E> {
+    /// Reports the opening size and the verifier cost when the variables are split into (x, y, z) with
+    /// the given numbers of variables: the opening has 2^x + 2^y + 1 group elements and 2^z evaluations
+    /// of f_star, the verifier runs MSMs of total size 2^x + 2^y + 2^z and two multi-pairings
+    pub fn cost_report(degrees: (usize, usize, usize)) -> CostReport {
+        let (degree_x, degree_y, degree_z) = (1 << degrees.0, 1 << degrees.1, 1 << degrees.2);
+
+        CostReport::new::<E>(
+            vec![degrees.0, degrees.1, degrees.2],
+            degree_x + degree_y + 1,
+            degree_z,
+            degree_x + degree_y + degree_z,
+            degree_x + degree_y + 2,
+        )
+    }
+
+    /// Reports the cost of every split of `num_variables` variables between x, y and z
+    pub fn cost_reports(num_variables: usize) -> Vec<CostReport> {
+        (0..=num_variables)
+            .flat_map(|x| (0..=num_variables - x).map(move |y| (x, y, num_variables - x - y)))
+            .map(Self::cost_report)
+            .collect()
+    }
+}
+
+impl<E: Pairing> KZH3<E> {
+    /// Same as `verify` but against a prepared verifier key
+    pub fn verify_prepared(
+        pvk: &KZH3PreparedVerifierKey<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH3Commitment<E>,
+        open: &KZH3Opening<E>,
+    ) -> Result<(), PCSError> {
+        let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y, pvk.degree_z], input, E::ScalarField::ZERO);
 
         // making sure D_x is well-formatted
-        let lhs = E::multi_pairing(&com.D_x, &srs.V_x).0;
-        let rhs = E::pairing(com.C, &srs.v).0;
+        let lhs = E::multi_pairing(&open.D_x, pvk.prepared_V_x.iter().cloned()).0;
+        let rhs = E::pairing(com.C, pvk.prepared_v.clone()).0;
 
-        assert_eq!(lhs, rhs);
+        if open.D_x.len() != pvk.degree_x || lhs != rhs {
+            return Err(PCSError::PairingCheckFailed);
+        }
 
         // making sure D_y is well formatted
-        assert_eq!(
-            E::G1::msm(
-                &com.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
-                EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
-            ).unwrap().into_affine(),
-            open.C_y,
-        );
+        let C_y = E::G1::msm(
+            &open.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+            EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
+        ).map_err(|_| PCSError::MSMCheckFailed)?.into_affine();
 
-        let lhs = E::multi_pairing(&open.D_y, &srs.V_y).0;
-        let rhs = E::pairing(open.C_y, &srs.v).0;
+        if C_y != open.C_y {
+            return Err(PCSError::MSMCheckFailed);
+        }
 
-        assert_eq!(lhs, rhs);
+        let lhs = E::multi_pairing(&open.D_y, pvk.prepared_V_y.iter().cloned()).0;
+        let rhs = E::pairing(open.C_y, pvk.prepared_v.clone()).0;
+
+        if open.D_y.len() != pvk.degree_y || lhs != rhs {
+            return Err(PCSError::PairingCheckFailed);
+        }
 
         // making sure f^star is well formatter
         let lhs = E::G1::msm(
-            srs.H_z.as_slice(),
+            pvk.H_z.as_slice(),
             open.f_star.evaluation_over_boolean_hypercube.as_slice(),
-        ).unwrap();
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
         let rhs = E::G1::msm(
             &open.D_y.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
             EqPolynomial::new(split_input[1].clone()).evals().as_slice(),
-        ).unwrap();
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
-        assert_eq!(lhs, rhs);
+        if lhs != rhs {
+            return Err(PCSError::MSMCheckFailed);
+        }
 
         // making sure the output of f_star and the given output are consistent
-        assert_eq!(open.f_star.evaluate(split_input[2].as_slice()), *output);
+        if open.f_star.num_variables != split_input[2].len()
+            || open.f_star.evaluate(split_input[2].as_slice()) != *output {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies many independent openings at once with a single multi-pairing and a single MSM, see
+    /// `KZH2::batch_verify`
+    pub fn batch_verify<R: Rng>(
+        pvk: &KZH3PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH3Commitment<E>],
+        opens: &[KZH3Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        if inputs.len() != coms.len() || outputs.len() != coms.len() || opens.len() != coms.len() {
+            return Err(PCSError::InvalidBatchSize);
+        }
+
+        let indices: Vec<usize> = (0..coms.len()).collect();
+        Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, indices.as_slice(), rng)
+    }
+
+    /// Same as `batch_verify` but when the batch fails it is bisected to find the invalid openings,
+    /// whose indices are returned. The indices missing an input, an output, a commitment or an
+    /// opening, when the slices have different lengths, are reported as invalid too
+    pub fn batch_verify_with_bisection<R: Rng>(
+        pvk: &KZH3PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH3Commitment<E>],
+        opens: &[KZH3Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>> {
+        let complete = inputs.len().min(outputs.len()).min(coms.len()).min(opens.len());
+        let total = inputs.len().max(outputs.len()).max(coms.len()).max(opens.len());
+
+        let indices: Vec<usize> = (0..complete).collect();
+        let mut invalid = bisect_failures(indices.as_slice(), &mut |subset| {
+            Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, subset, rng).is_ok()
+        });
+        invalid.extend(complete..total);
+
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+
+    /// Batch verifies the openings with the given indices, every slice must have an entry for each of them
+    fn batch_verify_subset<R: Rng>(
+        pvk: &KZH3PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH3Commitment<E>],
+        opens: &[KZH3Opening<E>],
+        indices: &[usize],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        // the evaluation checks only involve field operations, so they are done one by one
+        let mut eq_x = Vec::with_capacity(indices.len());
+        let mut eq_y = Vec::with_capacity(indices.len());
+        for &k in indices {
+            let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y, pvk.degree_z], inputs[k].as_slice(), E::ScalarField::ZERO);
+            let open = &opens[k];
+
+            if open.D_x.len() != pvk.degree_x || open.D_y.len() != pvk.degree_y {
+                return Err(PCSError::PairingCheckFailed);
+            }
+            if open.f_star.num_variables != split_input[2].len()
+                || open.f_star.evaluate(split_input[2].as_slice()) != outputs[k] {
+                return Err(PCSError::EvaluationCheckFailed);
+            }
+
+            eq_x.push(EqPolynomial::new(split_input[0].clone()).evals());
+            eq_y.push(EqPolynomial::new(split_input[1].clone()).evals());
+        }
+
+        let D_x: Vec<Vec<E::G1Affine>> = indices.iter().map(|&k| E::G1::normalize_batch(opens[k].D_x.as_slice())).collect();
+        let D_y: Vec<Vec<E::G1Affine>> = indices.iter().map(|&k| E::G1::normalize_batch(opens[k].D_y.as_slice())).collect();
+
+        // the two equations of each kind are separated by an extra random factor
+        let r: Vec<E::ScalarField> = get_batch_randomizers(indices.len(), rng);
+        let (delta_y, delta_z): (E::ScalarField, E::ScalarField) = (u128::rand(rng).into(), u128::rand(rng).into());
+        let r_y: Vec<_> = r.iter().map(|r| *r * delta_y).collect();
+        let r_z: Vec<_> = r.iter().map(|r| *r * delta_z).collect();
+
+        // Step 1: sum_k r_k * (e(D_x,k, V_x) - e(C_k, v)) + r_y,k * (e(D_y,k, V_y) - e(C_y,k, v)) = 0
+        let C: Vec<_> = indices.iter().map(|&k| coms[k].C).collect();
+        let C_y: Vec<_> = indices.iter().map(|&k| opens[k].C_y).collect();
+        let total_C = E::G1::msm_unchecked(C.as_slice(), r.as_slice()) + E::G1::msm_unchecked(C_y.as_slice(), r_y.as_slice());
+
+        let g1_elems = E::G1::normalize_batch(
+            combine_columns::<E::G1>(D_x.iter().map(|d| d.as_slice()).collect::<Vec<_>>().as_slice(), r.as_slice(), pvk.degree_x)
+                .into_iter()
+                .chain(combine_columns::<E::G1>(D_y.iter().map(|d| d.as_slice()).collect::<Vec<_>>().as_slice(), r_y.as_slice(), pvk.degree_y))
+                .chain(std::iter::once(-total_C))
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        let g2_elems: Vec<_> = pvk.prepared_V_x.iter().cloned()
+            .chain(pvk.prepared_V_y.iter().cloned())
+            .chain(std::iter::once(pvk.prepared_v.clone()))
+            .collect();
+
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        // Step 2: sum_k r_k * (MSM(D_x,k, eq(x_k)) - C_y,k) + r_z,k * (MSM(H_z, f_star_k) - MSM(D_y,k, eq(y_k))) = 0
+        let mut scalars = vec![E::ScalarField::ZERO; pvk.degree_z];
+        let mut bases = pvk.H_z.clone();
+        for (i, &k) in indices.iter().enumerate() {
+            for (scalar, f) in scalars[..pvk.degree_z].iter_mut().zip(opens[k].f_star.evaluation_over_boolean_hypercube.iter()) {
+                *scalar += r_z[i] * f;
+            }
+            scalars.extend(eq_x[i].iter().map(|e| r[i] * e));
+            bases.extend_from_slice(D_x[i].as_slice());
+            scalars.push(-r[i]);
+            bases.push(C_y[i]);
+            scalars.extend(eq_y[i].iter().map(|e| -(r_z[i] * e)));
+            bases.extend_from_slice(D_y[i].as_slice());
+        }
+
+        if !E::G1::msm_unchecked(bases.as_slice(), scalars.as_slice()).is_zero() {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Updates a commitment and its hint in place after f[index] += delta for every (index, delta),
+    /// only the SRS elements of the touched positions are used
+    pub fn update_commitment(
+        srs: &KZH3SRS<E>,
+        com: &mut KZH3Commitment<E>,
+        hint: &mut KZH3CommitmentHint<E>,
+        deltas: &[(usize, E::ScalarField)],
+    ) {
+        assert_eq!(hint.D_x.len(), srs.degree_x, "hint does not match the srs");
+
+        let mut C = com.C.into_group();
+        for (index, delta) in deltas {
+            assert!(*index < srs.H_xyz.len(), "index out of range for the srs");
+            let (i_x, i_y, i_z) = decompose_index(*index, srs.degree_y, srs.degree_z);
+
+            C += srs.H_xyz[*index].mul(delta);
+            hint.D_x[i_x] += srs.H_yz[i_y * srs.degree_z + i_z].mul(delta);
+        }
+        com.C = C.into_affine();
+    }
+}
+
+impl<E: Pairing> KZH3SRS<E> {
+    /// Checks that H_xyz, H_yz and H_z are the tau_x * tau_y * tau_z, tau_y * tau_z and tau_z multiples
+    /// of the same bases, where the trapdoors are defined by V_x, V_y and V_z. Every level is checked
+    /// with a random linear combination, so the cost is a few MSMs and six pairings
+    pub fn check_well_formed<R: Rng>(&self, rng: &mut R) -> Result<(), SRSError> {
+        if self.H_xyz.len() != self.degree_x * self.degree_y * self.degree_z
+            || self.H_yz.len() != self.degree_y * self.degree_z
+            || self.H_z.len() != self.degree_z
+            || self.V_x.len() != self.degree_x
+            || self.V_y.len() != self.degree_y
+            || self.v.is_zero() {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        if !check_proportional::<E, _>(self.H_z.as_slice(), self.V_z.as_slice(), rng)
+            || !check_level::<E, _>(self.H_yz.as_slice(), self.H_z.as_slice(), self.V_y.as_slice(), &self.v, rng)
+            || !check_level::<E, _>(self.H_xyz.as_slice(), self.H_yz.as_slice(), self.V_x.as_slice(), &self.v, rng) {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> KZH3SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the SRS for a smaller maximum degree, the variables are split evenly if the SRS is
+    /// large enough in every dimension and otherwise as close to evenly as its shape allows, it fails
+    /// if the SRS supports fewer variables
+    pub fn trim(&self, maximum_degree: usize) -> Result<KZH3SRS<E>, SRSError> {
+        let (degree_x, degree_y, degree_z) = KZH3::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        let degrees = trimmed_degrees(
+            &[degree_x, degree_y, degree_z],
+            &[self.degree_x.log_2(), self.degree_y.log_2(), self.degree_z.log_2()],
+            maximum_degree,
+        ).ok_or(SRSError::TrimmedDegreeTooLarge)?;
+
+        self.trim_with_degrees((degrees[0], degrees[1], degrees[2]))
+    }
+
+    /// Derives the SRS for the given log degrees by keeping the elements whose x, y and z indices
+    /// are below the new degrees, the trapdoors of different indices are independent
+    pub fn trim_with_degrees(&self, degrees: (usize, usize, usize)) -> Result<KZH3SRS<E>, SRSError> {
+        let degree_x = trimmed_degree(degrees.0, self.degree_x)?;
+        let degree_y = trimmed_degree(degrees.1, self.degree_y)?;
+        let degree_z = trimmed_degree(degrees.2, self.degree_z)?;
+
+        let H_xyz = (0..degree_x * degree_y * degree_z)
+            .map(|i| {
+                let (i_x, i_y, i_z) = decompose_index(i, degree_y, degree_z);
+                self.H_xyz[(i_x * self.degree_y + i_y) * self.degree_z + i_z]
+            }).collect();
+
+        let H_yz = (0..degree_y * degree_z)
+            .map(|i| {
+                let (i_y, i_z) = (i / degree_z, i % degree_z);
+                self.H_yz[i_y * self.degree_z + i_z]
+            }).collect();
+
+        Ok(KZH3SRS {
+            degree_x,
+            degree_y,
+            degree_z,
+            H_xyz,
+            H_yz,
+            H_z: self.H_z[..degree_z].to_vec(),
+            V_x: self.V_x[..degree_x].to_vec(),
+            V_y: self.V_y[..degree_y].to_vec(),
+            V_z: self.V_z[..degree_z].to_vec(),
+            v: self.v,
+            prepared: SRSCache::default(),
+        })
     }
 }
 
@@ -286,6 +625,58 @@ impl<E: Pairing> ToAffine<E> for KZH3Commitment<E> {
     }
 }
 
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH3Commitment<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH3Commitment { C: (self.C + other.C).into_affine() }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH3Commitment { C: self.C.mul(r).into_affine() }
+    }
+
+    fn linear_combination(terms: &[(E::ScalarField, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        // accumulate in projective form to normalize only once
+        let C = terms.iter().fold(E::G1::ZERO, |acc, (coeff, com)| acc + com.C.mul(coeff));
+        KZH3Commitment { C: C.into_affine() }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH3CommitmentHint<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH3CommitmentHint {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH3CommitmentHint {
+            D_x: scale_vector(&self.D_x, r),
+        }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH3Opening<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH3Opening {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+            D_y: add_vectors(&self.D_y, &other.D_y),
+            C_y: (self.C_y + other.C_y).into_affine(),
+            f_star: self.f_star.add(&other.f_star),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH3Opening {
+            D_x: scale_vector(&self.D_x, r),
+            D_y: scale_vector(&self.D_y, r),
+            C_y: self.C_y.mul(r).into_affine(),
+            f_star: self.f_star.scale(r),
+        }
+    }
+}
+
 fn decompose_index(i: usize, degree_y: usize, degree_z: usize) -> (usize, usize, usize) {
     // Compute i_z first, as it is the highest order term
     let i_x = i / (degree_y * degree_z);
@@ -305,7 +696,8 @@ fn decompose_index(i: usize, degree_y: usize, degree_z: usize) -> (usize, usize,
 #[cfg(test)]
 mod tests {
     use crate::constant_for_curves::{ScalarField as F, E};
-    use crate::kzh::kzh3::{decompose_index, KZH3, KZH3SRS};
+    use crate::kzh::errors::SRSError;
+    use crate::kzh::kzh3::{decompose_index, KZH3PreparedVerifierKey, KZH3, KZH3SRS};
     use crate::kzh::KZH;
     use crate::math::Math;
     use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
@@ -344,12 +736,135 @@ mod tests {
         let eval = polynomial.evaluate(input.as_slice());
 
         // commit to the polynomial
-        let c = KZH3::commit(&srs, &polynomial);
+        let (c, hint) = KZH3::commit(&srs, &polynomial);
 
         // open it
-        let open = KZH3::open(&srs, input.as_slice(), &c, &polynomial);
+        let open = KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
 
         // verify the commit
-        KZH3::verify(&srs, input.as_slice(), &eval, &c, &open);
+        KZH3::verify(&srs, input.as_slice(), &eval, &c, &open).unwrap();
+    }
+
+    #[test]
+    fn test_prepared_verifier_key() {
+        let srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        let pvk = KZH3PreparedVerifierKey::prepare(&srs);
+
+        let input: Vec<F> = (0..9)
+            .map(|_| F::rand(&mut thread_rng()))
+            .collect();
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(9, &mut thread_rng());
+        let eval = polynomial.evaluate(input.as_slice());
+
+        let (c, hint) = KZH3::commit(&srs, &polynomial);
+        let open = KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
+
+        KZH3::verify_prepared(&pvk, input.as_slice(), &eval, &c, &open).unwrap();
+        assert!(KZH3::verify_prepared(&pvk, input.as_slice(), &(eval + F::from(1u64)), &c, &open).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        let pvk = KZH3PreparedVerifierKey::prepare(&srs);
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut coms = Vec::new();
+        let mut opens = Vec::new();
+        for _ in 0..5 {
+            let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(9, &mut thread_rng());
+            let input: Vec<F> = (0..9).map(|_| F::rand(&mut thread_rng())).collect();
+            let (com, hint) = KZH3::commit(&srs, &polynomial);
+
+            opens.push(KZH3::open(&srs, input.as_slice(), &hint, &polynomial));
+            outputs.push(polynomial.evaluate(input.as_slice()));
+            inputs.push(input);
+            coms.push(com);
+        }
+
+        KZH3::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).unwrap();
+
+        // a wrong evaluation and a tampered opening are caught, bisection tells which ones
+        outputs[3] += F::from(1u64);
+        opens[1].D_x.swap(0, 1);
+        assert!(KZH3::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).is_err());
+        assert_eq!(
+            KZH3::batch_verify_with_bisection(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()),
+            Err(vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn test_check_well_formed() {
+        let mut srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        // a single wrong element in any level is detected
+        srs.H_xyz[100] = srs.H_xyz[101];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+
+        let mut srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        srs.H_z[1] = srs.H_z[2];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+    }
+
+    #[test]
+    fn test_trim() {
+        let srs: KZH3SRS<E> = KZH3::setup(10, &mut thread_rng());
+        let trimmed = srs.trim(7).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y, trimmed.degree_z), (8, 4, 4));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+
+        // trimming to more variables than the srs supports is an error
+        assert!(matches!(srs.trim(11), Err(SRSError::TrimmedDegreeTooLarge)));
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(7, &mut thread_rng());
+        let input: Vec<F> = (0..7).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZH3::commit(&trimmed, &polynomial);
+        let open = KZH3::open(&trimmed, input.as_slice(), &hint, &polynomial);
+        KZH3::verify(&trimmed, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_setup_with_degrees() {
+        let srs: KZH3SRS<E> = KZH3::setup_with_degrees((1, 2, 5), &mut thread_rng());
+        assert_eq!((srs.degree_x, srs.degree_y, srs.degree_z), (2, 4, 32));
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZH3::commit(&srs, &polynomial);
+        let open = KZH3::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZH3::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+
+        let report = KZH3::<E>::cost_report((1, 2, 5));
+        assert_eq!(report.proof_group_elements, open.D_x.len() + open.D_y.len() + 1);
+        assert_eq!(report.proof_field_elements, open.f_star.evaluation_over_boolean_hypercube.len());
+
+        // one report per split, i.e. (n + 1)(n + 2) / 2 of them
+        let reports = KZH3::<E>::cost_reports(8);
+        assert_eq!(reports.len(), 45);
+        assert!(reports.contains(&report));
+    }
+
+    #[test]
+    fn test_update_commitment() {
+        let srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+
+        let mut polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::random_binary(9, &mut thread_rng());
+        let (mut com, mut hint) = KZH3::commit(&srs, &polynomial);
+
+        let deltas: Vec<_> = [3, 64, 65, 511].iter()
+            .map(|index| (*index, F::rand(&mut thread_rng())))
+            .collect();
+        for (index, delta) in deltas.iter() {
+            polynomial.evaluation_over_boolean_hypercube[*index] += delta;
+        }
+
+        KZH3::update_commitment(&srs, &mut com, &mut hint, deltas.as_slice());
+        assert_eq!((com, hint), KZH3::commit(&srs, &polynomial));
     }
 }
@@ -1,4 +1,5 @@
-use crate::kzh::KZH;
+use crate::kzh::errors::{PCSError, SRSError};
+use crate::kzh::{add_vectors, bisect_failures, check_level, check_proportional, combine_columns, get_batch_randomizers, scale_vector, split_input_by_degrees, trimmed_degree, trimmed_degrees, CostReport, Homomorphic, SRSCache, KZH};
 use crate::math::Math;
 use crate::nexus_spartan::commitment_traits::ToAffine;
 use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
@@ -6,8 +7,8 @@ use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial
 use crate::transcript::transcript::{AppendToTranscript, Transcript};
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::pairing::Pairing;
-use ark_ec::{AffineRepr, VariableBaseMSM};
-use ark_ff::{AdditiveGroup, PrimeField};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{AdditiveGroup, One, PrimeField, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
 use derivative::Derivative;
@@ -42,10 +43,15 @@ pub struct KZH4SRS<E: Pairing> {
     pub V_t: Vec<E::G2Affine>,
 
     pub v: E::G2Affine,
+    /// the prepared verifier key, computed on the first verification, see `prepared_verifier_key`
+    pub prepared: SRSCache<KZH4PreparedVerifierKey<E>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
 pub struct KZH4Opening<E: Pairing> {
+    pub D_x: Vec<E::G1>,
+    /// D_y[j] is the commitment to f(x, j, ., .), i.e. the rows of the hint D_y combined at x
+    pub D_y: Vec<E::G1>,
     pub D_z: Vec<E::G1>,
     pub f_star: MultilinearPolynomial<E::ScalarField>,
 }
@@ -62,10 +68,67 @@ pub struct KZH4Opening<E: Pairing> {
 )]
 pub struct KZH4Commitment<E: Pairing> {
     pub C: E::G1Affine,
+}
+
+/// Prover-only data of a commitment, kept by the prover to open it and never sent to the verifier
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct KZH4CommitmentHint<E: Pairing> {
     pub D_x: Vec<E::G1>,
     pub D_y: Vec<E::G1>,
 }
 
+/// The part of the SRS needed for verification, with the G2 elements prepared for use in pairings
+#[derive(Clone, Debug)]
+pub struct KZH4PreparedVerifierKey<E: Pairing> {
+    pub degree_x: usize,
+    pub degree_y: usize,
+    pub degree_z: usize,
+    pub degree_t: usize,
+    pub H_t: Vec<E::G1Affine>,
+    /// V_x, prepared for use in pairings
+    pub prepared_V_x: Vec<E::G2Prepared>,
+    /// V_y, prepared for use in pairings
+    pub prepared_V_y: Vec<E::G2Prepared>,
+    /// V_z, prepared for use in pairings
+    pub prepared_V_z: Vec<E::G2Prepared>,
+    /// v, prepared for use in pairings
+    pub prepared_v: E::G2Prepared,
+}
+
+impl<E: Pairing> KZH4PreparedVerifierKey<E> {
+    /// prepare `KZH4PreparedVerifierKey` from `KZH4SRS`
+    pub fn prepare(srs: &KZH4SRS<E>) -> Self {
+        KZH4PreparedVerifierKey {
+            degree_x: srs.degree_x,
+            degree_y: srs.degree_y,
+            degree_z: srs.degree_z,
+            degree_t: srs.degree_t,
+            H_t: srs.H_t.clone(),
+            prepared_V_x: srs.V_x.iter().map(|v| v.into()).collect(),
+            prepared_V_y: srs.V_y.iter().map(|v| v.into()).collect(),
+            prepared_V_z: srs.V_z.iter().map(|v| v.into()).collect(),
+            prepared_v: srs.v.into(),
+        }
+    }
+}
+
+impl<E: Pairing> KZH4SRS<E> {
+    /// Returns the prepared verifier key of the SRS, it is prepared on the first call and reused by
+    /// every later verification against the SRS
+    pub fn prepared_verifier_key(&self) -> &KZH4PreparedVerifierKey<E> {
+        self.prepared.get_or_init(|| KZH4PreparedVerifierKey::prepare(self))
+    }
+}
+
 impl<E: Pairing> KZH<E> for KZH4<E>
 where
     <E as Pairing>::ScalarField: Absorb,
@@ -74,6 +137,7 @@ where
     type Degree = (usize, usize, usize, usize);
     type SRS = KZH4SRS<E>;
     type Commitment = KZH4Commitment<E>;
+    type CommitmentHint = KZH4CommitmentHint<E>;
     type Opening = KZH4Opening<E>;
 
     fn split_input<T: Clone>(srs: &Self::SRS, input: &[T], default: T) -> Vec<Vec<T>> {
@@ -106,8 +170,8 @@ where
         }
     }
 
-    fn setup<R: Rng>(maximum_degree: usize, rng: &mut R) -> Self::SRS {
-        let (degree_x, degree_y, degree_z, degree_t) = Self::get_degree_from_maximum_supported_degree(maximum_degree);
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS {
+        let (degree_x, degree_y, degree_z, degree_t) = degrees;
         let (degree_x, degree_y, degree_z, degree_t) = (1 << degree_x, 1 << degree_y, 1 << degree_z, 1 << degree_t);
 
         let (g, v) = (E::G1Affine::rand(rng), E::G2Affine::rand(rng));
@@ -164,11 +228,13 @@ where
             V_z,
             V_t,
             v,
+            prepared: SRSCache::default(),
         }
     }
 
-    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Commitment {
+    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> (Self::Commitment, Self::CommitmentHint) {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2() + srs.degree_z.log_2() + srs.degree_t.log_2();
+        assert!(poly.num_variables <= len, "polynomial has more variables than the srs supports");
         let poly = poly.extend_number_of_variables(len);
 
         assert_eq!(poly.num_variables, len);
@@ -196,13 +262,13 @@ where
                 )
             }).collect::<Vec<_>>();
 
-        KZH4Commitment { C, D_x, D_y }
+        (KZH4Commitment { C }, KZH4CommitmentHint { D_x, D_y })
     }
 
     fn open(
         srs: &Self::SRS,
         input: &[E::ScalarField],
-        _com: &Self::Commitment,
+        hint: &Self::CommitmentHint,
         poly: &MultilinearPolynomial<E::ScalarField>
     ) -> Self::Opening {
         let len = srs.degree_x.log_2() + srs.degree_y.log_2() + srs.degree_z.log_2() + srs.degree_t.log_2();
@@ -214,6 +280,17 @@ where
 
         let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
 
+        // D_y[j] = sum_i eq(x, i) * hint.D_y[i * degree_y + j]
+        let eq_x = EqPolynomial::new(split_input[0].clone()).evals();
+        let D_y_affine = E::G1::normalize_batch(hint.D_y.as_slice());
+        let D_y = (0..srs.degree_y)
+            .into_iter()
+            .map(|j| {
+                let bases: Vec<_> = (0..srs.degree_x).map(|i| D_y_affine[i * srs.degree_y + j]).collect();
+                E::G1::msm_unchecked(bases.as_slice(), eq_x.as_slice())
+            })
+            .collect::<Vec<_>>();
+
         let D_z = (0..srs.degree_z)
             .into_iter()
             .map(|i| {
@@ -240,48 +317,343 @@ where
         }.as_slice());
 
         KZH4Opening {
+            D_x: hint.D_x.clone(),
+            D_y,
             D_z,
             f_star,
         }
     }
 
-    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) {
-        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+    /// The verifier key is prepared on the first verification against the SRS and cached in it, see
+    /// `KZH4SRS::prepared_verifier_key`
+    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) -> Result<(), PCSError> {
+        Self::verify_prepared(srs.prepared_verifier_key(), input, output, com, open)
+    }
+}
+
+impl<E: Pairing> KZH4<E> {
+    /// Reports the opening size and the verifier cost when the variables are split into (x, y, z, t)
+    /// with the given numbers of variables: the opening has 2^x + 2^y + 2^z group elements and 2^t
+    /// evaluations of f_star, the verifier runs MSMs of total size 2^x + 2^y + 2^z + 2^t and three
+    /// multi-pairings
+    pub fn cost_report(degrees: (usize, usize, usize, usize)) -> CostReport {
+        let (degree_x, degree_y, degree_z, degree_t) = (1 << degrees.0, 1 << degrees.1, 1 << degrees.2, 1 << degrees.3);
+
+        CostReport::new::<E>(
+            vec![degrees.0, degrees.1, degrees.2, degrees.3],
+            degree_x + degree_y + degree_z,
+            degree_t,
+            degree_x + degree_y + degree_z + degree_t,
+            degree_x + degree_y + degree_z + 3,
+        )
+    }
+
+    /// Reports the cost of every split of `num_variables` variables between x, y, z and t
+    pub fn cost_reports(num_variables: usize) -> Vec<CostReport> {
+        let mut reports = Vec::new();
+        for x in 0..=num_variables {
+            for y in 0..=num_variables - x {
+                for z in 0..=num_variables - x - y {
+                    reports.push(Self::cost_report((x, y, z, num_variables - x - y - z)));
+                }
+            }
+        }
+
+        reports
+    }
+}
+
+impl<E: Pairing> KZH4<E> {
+    /// Same as `verify` but against a prepared verifier key
+    pub fn verify_prepared(
+        pvk: &KZH4PreparedVerifierKey<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH4Commitment<E>,
+        open: &KZH4Opening<E>,
+    ) -> Result<(), PCSError> {
+        let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y, pvk.degree_z, pvk.degree_t], input, E::ScalarField::ZERO);
 
         // making sure D_x is well-formatted
-        let lhs = E::multi_pairing(&com.D_x, &srs.V_x).0;
-        let rhs = E::pairing(com.C, &srs.v).0;
+        let lhs = E::multi_pairing(&open.D_x, pvk.prepared_V_x.iter().cloned()).0;
+        let rhs = E::pairing(com.C, pvk.prepared_v.clone()).0;
+
+        if open.D_x.len() != pvk.degree_x || lhs != rhs {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        // making sure D_y is well formatted, i.e. consistent with D_x at x
+        let C_y = E::G1::msm(
+            &open.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+            EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
-        assert_eq!(lhs, rhs);
+        let lhs = E::multi_pairing(&open.D_y, pvk.prepared_V_y.iter().cloned()).0;
+        let rhs = E::pairing(C_y, pvk.prepared_v.clone()).0;
 
-        let concatenated: Vec<E::ScalarField> = split_input[0].iter().chain(split_input[1].iter()).cloned().collect();
+        if open.D_y.len() != pvk.degree_y || lhs != rhs {
+            return Err(PCSError::PairingCheckFailed);
+        }
 
-        // making sure D_y is well formatted
         let new_c = E::G1::msm(
-            &com.D_y.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
-            EqPolynomial::new(concatenated).evals().as_slice(),
-        ).unwrap();
+            &open.D_y.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+            EqPolynomial::new(split_input[1].clone()).evals().as_slice(),
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
-        let lhs = E::multi_pairing(&open.D_z, &srs.V_z).0;
-        let rhs = E::pairing(new_c, &srs.v).0;
+        let lhs = E::multi_pairing(&open.D_z, pvk.prepared_V_z.iter().cloned()).0;
+        let rhs = E::pairing(new_c, pvk.prepared_v.clone()).0;
 
-        assert_eq!(lhs, rhs);
+        if open.D_z.len() != pvk.degree_z || lhs != rhs {
+            return Err(PCSError::PairingCheckFailed);
+        }
 
         // making sure f^star is well formatter
         let lhs = E::G1::msm(
-            srs.H_t.as_slice(),
+            pvk.H_t.as_slice(),
             open.f_star.evaluation_over_boolean_hypercube.as_slice(),
-        ).unwrap();
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
         let rhs = E::G1::msm(
             &open.D_z.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
             EqPolynomial::new(split_input[2].clone()).evals().as_slice(),
-        ).unwrap();
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
 
-        assert_eq!(lhs, rhs);
+        if lhs != rhs {
+            return Err(PCSError::MSMCheckFailed);
+        }
 
         // making sure the output of f_star and the given output are consistent
-        assert_eq!(open.f_star.evaluate(split_input[3].as_slice()), *output);
+        if open.f_star.num_variables != split_input[3].len()
+            || open.f_star.evaluate(split_input[3].as_slice()) != *output {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies many independent openings at once with a single multi-pairing and a single MSM, see
+    /// `KZH2::batch_verify`
+    pub fn batch_verify<R: Rng>(
+        pvk: &KZH4PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH4Commitment<E>],
+        opens: &[KZH4Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        if inputs.len() != coms.len() || outputs.len() != coms.len() || opens.len() != coms.len() {
+            return Err(PCSError::InvalidBatchSize);
+        }
+
+        let indices: Vec<usize> = (0..coms.len()).collect();
+        Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, indices.as_slice(), rng)
+    }
+
+    /// Same as `batch_verify` but when the batch fails it is bisected to find the invalid openings,
+    /// whose indices are returned. The indices missing an input, an output, a commitment or an
+    /// opening, when the slices have different lengths, are reported as invalid too
+    pub fn batch_verify_with_bisection<R: Rng>(
+        pvk: &KZH4PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH4Commitment<E>],
+        opens: &[KZH4Opening<E>],
+        rng: &mut R,
+    ) -> Result<(), Vec<usize>> {
+        let complete = inputs.len().min(outputs.len()).min(coms.len()).min(opens.len());
+        let total = inputs.len().max(outputs.len()).max(coms.len()).max(opens.len());
+
+        let indices: Vec<usize> = (0..complete).collect();
+        let mut invalid = bisect_failures(indices.as_slice(), &mut |subset| {
+            Self::batch_verify_subset(pvk, inputs, outputs, coms, opens, subset, rng).is_ok()
+        });
+        invalid.extend(complete..total);
+
+        if invalid.is_empty() { Ok(()) } else { Err(invalid) }
+    }
+
+    /// Batch verifies the openings with the given indices, every slice must have an entry for each of them
+    fn batch_verify_subset<R: Rng>(
+        pvk: &KZH4PreparedVerifierKey<E>,
+        inputs: &[Vec<E::ScalarField>],
+        outputs: &[E::ScalarField],
+        coms: &[KZH4Commitment<E>],
+        opens: &[KZH4Opening<E>],
+        indices: &[usize],
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        // the evaluation checks only involve field operations, so they are done one by one
+        let mut eq_x = Vec::with_capacity(indices.len());
+        let mut eq_y = Vec::with_capacity(indices.len());
+        let mut eq_z = Vec::with_capacity(indices.len());
+        for &k in indices {
+            let split_input = split_input_by_degrees(&[pvk.degree_x, pvk.degree_y, pvk.degree_z, pvk.degree_t], inputs[k].as_slice(), E::ScalarField::ZERO);
+            let open = &opens[k];
+
+            if open.D_x.len() != pvk.degree_x || open.D_y.len() != pvk.degree_y || open.D_z.len() != pvk.degree_z {
+                return Err(PCSError::PairingCheckFailed);
+            }
+            if open.f_star.num_variables != split_input[3].len()
+                || open.f_star.evaluate(split_input[3].as_slice()) != outputs[k] {
+                return Err(PCSError::EvaluationCheckFailed);
+            }
+
+            eq_x.push(EqPolynomial::new(split_input[0].clone()).evals());
+            eq_y.push(EqPolynomial::new(split_input[1].clone()).evals());
+            eq_z.push(EqPolynomial::new(split_input[2].clone()).evals());
+        }
+
+        let D_x: Vec<Vec<E::G1Affine>> = indices.iter().map(|&k| E::G1::normalize_batch(opens[k].D_x.as_slice())).collect();
+        let D_y: Vec<Vec<E::G1Affine>> = indices.iter().map(|&k| E::G1::normalize_batch(opens[k].D_y.as_slice())).collect();
+        let D_z: Vec<Vec<E::G1Affine>> = indices.iter().map(|&k| E::G1::normalize_batch(opens[k].D_z.as_slice())).collect();
+
+        // the three pairing equations of each opening are separated by extra random factors
+        let r: Vec<E::ScalarField> = get_batch_randomizers(indices.len(), rng);
+        let (delta_y, delta_z): (E::ScalarField, E::ScalarField) = (u128::rand(rng).into(), u128::rand(rng).into());
+        let r_y: Vec<_> = r.iter().map(|r| *r * delta_y).collect();
+        let r_z: Vec<_> = r.iter().map(|r| *r * delta_z).collect();
+
+        // Step 1: the intermediate commitments C_y,k = MSM(D_x,k, eq(x_k)) and C_z,k = MSM(D_y,k, eq(y_k)) are
+        // never computed on their own, their randomized sum is folded in the MSM for the G1 side of e(., v)
+        let mut bases: Vec<_> = indices.iter().map(|&k| coms[k].C).collect();
+        let mut scalars = r.clone();
+        for i in 0..indices.len() {
+            bases.extend_from_slice(D_x[i].as_slice());
+            scalars.extend(eq_x[i].iter().map(|e| r_y[i] * e));
+            bases.extend_from_slice(D_y[i].as_slice());
+            scalars.extend(eq_y[i].iter().map(|e| r_z[i] * e));
+        }
+        let total_C = E::G1::msm_unchecked(bases.as_slice(), scalars.as_slice());
+
+        let columns = |D: &Vec<Vec<E::G1Affine>>, r: &[E::ScalarField], len: usize| {
+            combine_columns::<E::G1>(D.iter().map(|d| d.as_slice()).collect::<Vec<_>>().as_slice(), r, len)
+        };
+        let g1_elems = E::G1::normalize_batch(
+            columns(&D_x, r.as_slice(), pvk.degree_x)
+                .into_iter()
+                .chain(columns(&D_y, r_y.as_slice(), pvk.degree_y))
+                .chain(columns(&D_z, r_z.as_slice(), pvk.degree_z))
+                .chain(std::iter::once(-total_C))
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        let g2_elems: Vec<_> = pvk.prepared_V_x.iter().cloned()
+            .chain(pvk.prepared_V_y.iter().cloned())
+            .chain(pvk.prepared_V_z.iter().cloned())
+            .chain(std::iter::once(pvk.prepared_v.clone()))
+            .collect();
+
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        // Step 2: sum_k r_k * (MSM(H_t, f_star_k) - MSM(D_z,k, eq(z_k))) = 0 as a single MSM
+        let mut scalars = vec![E::ScalarField::ZERO; pvk.degree_t];
+        let mut bases = pvk.H_t.clone();
+        for (i, &k) in indices.iter().enumerate() {
+            for (scalar, f) in scalars[..pvk.degree_t].iter_mut().zip(opens[k].f_star.evaluation_over_boolean_hypercube.iter()) {
+                *scalar += r[i] * f;
+            }
+            scalars.extend(eq_z[i].iter().map(|e| -(r[i] * e)));
+            bases.extend_from_slice(D_z[i].as_slice());
+        }
+
+        if !E::G1::msm_unchecked(bases.as_slice(), scalars.as_slice()).is_zero() {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> KZH4SRS<E> {
+    /// Checks that each of H_xyzt, H_yzt, H_zt and H_t is the multiple of the next level by the
+    /// trapdoors defined by V_x, V_y, V_z and V_t. Every level is checked with a random linear
+    /// combination, so the cost is a few MSMs and eight pairings
+    pub fn check_well_formed<R: Rng>(&self, rng: &mut R) -> Result<(), SRSError> {
+        if self.H_xyzt.len() != self.degree_x * self.degree_y * self.degree_z * self.degree_t
+            || self.H_yzt.len() != self.degree_y * self.degree_z * self.degree_t
+            || self.H_zt.len() != self.degree_z * self.degree_t
+            || self.H_t.len() != self.degree_t
+            || self.V_x.len() != self.degree_x
+            || self.V_y.len() != self.degree_y
+            || self.V_z.len() != self.degree_z
+            || self.v.is_zero() {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        if !check_proportional::<E, _>(self.H_t.as_slice(), self.V_t.as_slice(), rng)
+            || !check_level::<E, _>(self.H_zt.as_slice(), self.H_t.as_slice(), self.V_z.as_slice(), &self.v, rng)
+            || !check_level::<E, _>(self.H_yzt.as_slice(), self.H_zt.as_slice(), self.V_y.as_slice(), &self.v, rng)
+            || !check_level::<E, _>(self.H_xyzt.as_slice(), self.H_yzt.as_slice(), self.V_x.as_slice(), &self.v, rng) {
+            return Err(SRSError::MalformedSRS);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing> KZH4SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the SRS for a smaller maximum degree, the variables are split evenly if the SRS is
+    /// large enough in every dimension and otherwise as close to evenly as its shape allows, it fails
+    /// if the SRS supports fewer variables
+    pub fn trim(&self, maximum_degree: usize) -> Result<KZH4SRS<E>, SRSError> {
+        let (degree_x, degree_y, degree_z, degree_t) = KZH4::<E>::get_degree_from_maximum_supported_degree(maximum_degree);
+        let degrees = trimmed_degrees(
+            &[degree_x, degree_y, degree_z, degree_t],
+            &[self.degree_x.log_2(), self.degree_y.log_2(), self.degree_z.log_2(), self.degree_t.log_2()],
+            maximum_degree,
+        ).ok_or(SRSError::TrimmedDegreeTooLarge)?;
+
+        self.trim_with_degrees((degrees[0], degrees[1], degrees[2], degrees[3]))
+    }
+
+    /// Derives the SRS for the given log degrees by keeping the elements whose x, y, z and t
+    /// indices are below the new degrees, the trapdoors of different indices are independent
+    pub fn trim_with_degrees(&self, degrees: (usize, usize, usize, usize)) -> Result<KZH4SRS<E>, SRSError> {
+        let degree_x = trimmed_degree(degrees.0, self.degree_x)?;
+        let degree_y = trimmed_degree(degrees.1, self.degree_y)?;
+        let degree_z = trimmed_degree(degrees.2, self.degree_z)?;
+        let degree_t = trimmed_degree(degrees.3, self.degree_t)?;
+
+        let H_xyzt = (0..degree_x * degree_y * degree_z * degree_t)
+            .map(|i| {
+                let (i_x, i_y, i_z, i_t) = decompose_index(i, degree_y, degree_z, degree_t);
+                self.H_xyzt[((i_x * self.degree_y + i_y) * self.degree_z + i_z) * self.degree_t + i_t]
+            }).collect();
+
+        let H_yzt = (0..degree_y * degree_z * degree_t)
+            .map(|i| {
+                let (_, i_y, i_z, i_t) = decompose_index(i, degree_y, degree_z, degree_t);
+                self.H_yzt[(i_y * self.degree_z + i_z) * self.degree_t + i_t]
+            }).collect();
+
+        let H_zt = (0..degree_z * degree_t)
+            .map(|i| {
+                let (i_z, i_t) = (i / degree_t, i % degree_t);
+                self.H_zt[i_z * self.degree_t + i_t]
+            }).collect();
+
+        Ok(KZH4SRS {
+            degree_x,
+            degree_y,
+            degree_z,
+            degree_t,
+            H_xyzt,
+            H_yzt,
+            H_zt,
+            H_t: self.H_t[..degree_t].to_vec(),
+            V_x: self.V_x[..degree_x].to_vec(),
+            V_y: self.V_y[..degree_y].to_vec(),
+            V_z: self.V_z[..degree_z].to_vec(),
+            V_t: self.V_t[..degree_t].to_vec(),
+            v: self.v,
+            prepared: SRSCache::default(),
+        })
     }
 }
 
@@ -301,6 +673,60 @@ impl<E: Pairing> ToAffine<E> for KZH4Commitment<E> {
     }
 }
 
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH4Commitment<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH4Commitment { C: (self.C + other.C).into_affine() }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH4Commitment { C: self.C.mul(r).into_affine() }
+    }
+
+    fn linear_combination(terms: &[(E::ScalarField, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        // accumulate in projective form to normalize only once
+        let C = terms.iter().fold(E::G1::ZERO, |acc, (coeff, com)| acc + com.C.mul(coeff));
+        KZH4Commitment { C: C.into_affine() }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH4CommitmentHint<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH4CommitmentHint {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+            D_y: add_vectors(&self.D_y, &other.D_y),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH4CommitmentHint {
+            D_x: scale_vector(&self.D_x, r),
+            D_y: scale_vector(&self.D_y, r),
+        }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZH4Opening<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZH4Opening {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+            D_y: add_vectors(&self.D_y, &other.D_y),
+            D_z: add_vectors(&self.D_z, &other.D_z),
+            f_star: self.f_star.add(&other.f_star),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZH4Opening {
+            D_x: scale_vector(&self.D_x, r),
+            D_y: scale_vector(&self.D_y, r),
+            D_z: scale_vector(&self.D_z, r),
+            f_star: self.f_star.scale(r),
+        }
+    }
+}
+
 fn decompose_index(i: usize, degree_y: usize, degree_z: usize, degree_t: usize) -> (usize, usize, usize, usize) {
     // Compute i_z first, as it is the highest order term
     let i_x = i / (degree_y * degree_z * degree_t);
@@ -347,12 +773,136 @@ mod tests {
         let eval = polynomial.evaluate(input.as_slice());
 
         // commit to the polynomial
-        let com = KZH4::commit(&srs, &polynomial);
+        let (com, hint) = KZH4::commit(&srs, &polynomial);
 
         // open it
-        let open = KZH4::open(&srs, input.as_slice(), &com, &polynomial);
+        let open = KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
 
         // verify the commit
-        KZH4::verify(&srs, input.as_slice(), &eval, &com, &open);
+        KZH4::verify(&srs, input.as_slice(), &eval, &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_prepared_verifier_key() {
+        let srs: KZH4SRS<E> = KZH4::setup(10, &mut thread_rng());
+        let pvk = KZH4PreparedVerifierKey::prepare(&srs);
+
+        let input: Vec<F> = (0..10)
+            .map(|_| F::rand(&mut thread_rng()))
+            .collect();
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(10, &mut thread_rng());
+        let eval = polynomial.evaluate(input.as_slice());
+
+        let (c, hint) = KZH4::commit(&srs, &polynomial);
+        let open = KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
+
+        KZH4::verify_prepared(&pvk, input.as_slice(), &eval, &c, &open).unwrap();
+        assert!(KZH4::verify_prepared(&pvk, input.as_slice(), &(eval + F::from(1u64)), &c, &open).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let srs: KZH4SRS<E> = KZH4::setup(10, &mut thread_rng());
+        let pvk = KZH4PreparedVerifierKey::prepare(&srs);
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut coms = Vec::new();
+        let mut opens = Vec::new();
+        for _ in 0..5 {
+            let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(10, &mut thread_rng());
+            let input: Vec<F> = (0..10).map(|_| F::rand(&mut thread_rng())).collect();
+            let (com, hint) = KZH4::commit(&srs, &polynomial);
+
+            opens.push(KZH4::open(&srs, input.as_slice(), &hint, &polynomial));
+            outputs.push(polynomial.evaluate(input.as_slice()));
+            inputs.push(input);
+            coms.push(com);
+        }
+
+        KZH4::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).unwrap();
+
+        // a wrong evaluation and a tampered opening are caught, bisection tells which ones
+        outputs[3] += F::from(1u64);
+        opens[1].D_x.swap(0, 1);
+        assert!(KZH4::batch_verify(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()).is_err());
+        assert_eq!(
+            KZH4::batch_verify_with_bisection(&pvk, &inputs, &outputs, &coms, &opens, &mut thread_rng()),
+            Err(vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn test_check_well_formed() {
+        let mut srs: KZH4SRS<E> = KZH4::setup(8, &mut thread_rng());
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        // a single wrong element in any level is detected
+        srs.H_yzt[7] = srs.H_yzt[8];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+
+        let mut srs: KZH4SRS<E> = KZH4::setup(8, &mut thread_rng());
+        srs.V_t[0] = srs.V_t[1];
+        assert!(matches!(srs.check_well_formed(&mut thread_rng()), Err(SRSError::MalformedSRS)));
+    }
+
+    #[test]
+    fn test_trim() {
+        let srs: KZH4SRS<E> = KZH4::setup(11, &mut thread_rng());
+        let trimmed = srs.trim(6).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y, trimmed.degree_z, trimmed.degree_t), (4, 4, 2, 2));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+
+        // trimming to more variables than the srs supports is an error
+        assert!(matches!(srs.trim(12), Err(SRSError::TrimmedDegreeTooLarge)));
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(6, &mut thread_rng());
+        let input: Vec<F> = (0..6).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZH4::commit(&trimmed, &polynomial);
+        let open = KZH4::open(&trimmed, input.as_slice(), &hint, &polynomial);
+        KZH4::verify(&trimmed, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_trim_unbalanced() {
+        // the even split (2, 2, 1, 1) does not fit in 2 values of x, so the largest dimensions are trimmed
+        let srs: KZH4SRS<E> = KZH4::setup_with_degrees((1, 2, 2, 3), &mut thread_rng());
+        let trimmed = srs.trim(6).unwrap();
+        assert_eq!((trimmed.degree_x, trimmed.degree_y, trimmed.degree_z, trimmed.degree_t), (2, 4, 4, 2));
+        trimmed.check_well_formed(&mut thread_rng()).unwrap();
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(6, &mut thread_rng());
+        let input: Vec<F> = (0..6).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZH4::commit(&trimmed, &polynomial);
+        let open = KZH4::open(&trimmed, input.as_slice(), &hint, &polynomial);
+        KZH4::verify(&trimmed, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+
+        assert!(matches!(srs.trim_with_degrees((2, 2, 1, 1)), Err(SRSError::TrimmedDegreeTooLarge)));
+        assert!(matches!(srs.trim(9), Err(SRSError::TrimmedDegreeTooLarge)));
+    }
+
+    #[test]
+    fn test_setup_with_degrees() {
+        let srs: KZH4SRS<E> = KZH4::setup_with_degrees((1, 3, 2, 2), &mut thread_rng());
+        assert_eq!((srs.degree_x, srs.degree_y, srs.degree_z, srs.degree_t), (2, 8, 4, 4));
+        srs.check_well_formed(&mut thread_rng()).unwrap();
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZH4::commit(&srs, &polynomial);
+        let open = KZH4::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZH4::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+
+        let report = KZH4::<E>::cost_report((1, 3, 2, 2));
+        assert_eq!(report.proof_group_elements, open.D_x.len() + open.D_y.len() + open.D_z.len());
+        assert_eq!(report.proof_field_elements, open.f_star.evaluation_over_boolean_hypercube.len());
+
+        // one report per split, i.e. (n + 1)(n + 2)(n + 3) / 6 of them
+        let reports = KZH4::<E>::cost_reports(8);
+        assert_eq!(reports.len(), 165);
+        assert!(reports.contains(&report));
     }
 }
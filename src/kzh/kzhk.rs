@@ -0,0 +1,534 @@
+use crate::kzh::errors::PCSError;
+use crate::kzh::{add_vectors, scale_vector, Homomorphic, KZH};
+use crate::math::Math;
+use crate::nexus_spartan::commitment_traits::ToAffine;
+use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
+use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+use crate::transcript::transcript::{AppendToTranscript, Transcript};
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{AdditiveGroup, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use derivative::Derivative;
+use rand::Rng;
+use rayon::iter::IntoParallelIterator;
+use rayon::iter::ParallelIterator;
+use std::marker::PhantomData;
+use std::ops::Mul;
+
+/// KZH where the hypercube is split into K >= 2 dimensions x_0, ..., x_{K-1}. It follows the same
+/// construction as KZH2/KZH3/KZH4 but is a separate, minimal scheme: it has no prepared verifier
+/// key, batch verification, trimming, well-formedness check, cost report, hiding commitments,
+/// ceremony or poly_commit adapter, use the fixed-dimension schemes when those are needed
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct KZHk<E: Pairing, const K: usize> {
+    phantom: PhantomData<E>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct KZHkSRS<E: Pairing> {
+    /// degrees[j] = 2 ^ length of the j-th variable
+    pub degrees: Vec<usize>,
+
+    /// H[j] contains g * tau_j[i_j] * ... * tau_{K-1}[i_{K-1}] for all indices (i_j, ..., i_{K-1})
+    pub H: Vec<Vec<E::G1Affine>>,
+    /// V[j] contains v * tau_j[i] for all i
+    pub V: Vec<Vec<E::G2Affine>>,
+
+    pub v: E::G2Affine,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct KZHkOpening<E: Pairing> {
+    /// D_x[i] is the commitment to f(i, .) w.r.t. H[1]
+    pub D_x: Vec<E::G1>,
+    /// D[j - 1][i] is the commitment to f(r_0, ..., r_{j-1}, i, .) w.r.t. H[j + 1], for j = 1, ..., K - 2
+    pub D: Vec<Vec<E::G1>>,
+    pub f_star: MultilinearPolynomial<E::ScalarField>,
+}
+
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct KZHkCommitment<E: Pairing> {
+    pub C: E::G1Affine,
+}
+
+/// Prover-only data of a commitment, kept by the prover to open it and never sent to the verifier
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct KZHkCommitmentHint<E: Pairing> {
+    /// D_x[i] is the commitment to f(i, .) w.r.t. H[1]
+    pub D_x: Vec<E::G1>,
+}
+
+impl<E: Pairing> KZHkSRS<E> {
+    /// Returns the total number of variables supported by the srs
+    pub fn num_variables(&self) -> usize {
+        self.degrees.iter().map(|degree| degree.log_2()).sum()
+    }
+
+    /// Returns whether the srs is split into k dimensions, with H[j] and V[j] of matching sizes
+    pub fn has_dimensions(&self, k: usize) -> bool {
+        self.degrees.len() == k
+            && self.H.len() == k
+            && self.V.len() == k
+            && (0..k).all(|j| self.H[j].len() == self.suffix_size(j) && self.V[j].len() == self.degrees[j])
+    }
+
+    /// Returns the size of H[j], i.e. degrees[j] * ... * degrees[K-1]
+    fn suffix_size(&self, j: usize) -> usize {
+        self.degrees[j..].iter().product()
+    }
+}
+
+impl<E: Pairing, const K: usize> KZH<E> for KZHk<E, K>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    type Degree = Vec<usize>;
+    type SRS = KZHkSRS<E>;
+    type Commitment = KZHkCommitment<E>;
+    type CommitmentHint = KZHkCommitmentHint<E>;
+    type Opening = KZHkOpening<E>;
+
+    fn split_input<T: Clone>(srs: &Self::SRS, input: &[T], default: T) -> Vec<Vec<T>> {
+        let total_length = srs.num_variables();
+
+        // If r is smaller than the required length, extend it with zeros at the beginning
+        let mut extended_r = input.to_vec();
+        if input.len() < total_length {
+            let mut zeros = vec![default; total_length - input.len()];
+            zeros.extend(extended_r);  // Prepend zeros to the beginning
+            extended_r = zeros;
+        }
+
+        // Split the vector into K parts
+        let mut start = 0;
+        srs.degrees.iter()
+            .map(|degree| {
+                let part = extended_r[start..start + degree.log_2()].to_vec();
+                start += degree.log_2();
+                part
+            })
+            .collect()
+    }
+
+    fn get_degree_from_maximum_supported_degree(n: usize) -> Vec<usize> {
+        const { assert!(K >= 2, "KZHk needs at least two dimensions") };
+
+        // the first n % K dimensions get one extra variable
+        (0..K).map(|j| n / K + if j < n % K { 1 } else { 0 }).collect()
+    }
+
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS {
+        const { assert!(K >= 2, "KZHk needs at least two dimensions") };
+        assert_eq!(degrees.len(), K, "there should be one degree per dimension");
+
+        let degrees: Vec<usize> = degrees
+            .into_iter()
+            .map(|degree| 1 << degree)
+            .collect();
+
+        let (g, v) = (E::G1Affine::rand(rng), E::G2Affine::rand(rng));
+
+        let tau: Vec<Vec<E::ScalarField>> = degrees.iter()
+            .map(|degree| (0..*degree).map(|_| E::ScalarField::rand(rng)).collect())
+            .collect();
+
+        // trapdoor products for the suffixes, built from the last dimension to the first one
+        let mut scalars: Vec<Vec<E::ScalarField>> = vec![tau[K - 1].clone()];
+        for j in (0..K - 1).rev() {
+            let next = scalars.last().unwrap();
+            let current: Vec<_> = tau[j].iter()
+                .flat_map(|t| next.iter().map(move |s| *t * s))
+                .collect();
+            scalars.push(current);
+        }
+        scalars.reverse();
+
+        let H: Vec<Vec<_>> = scalars.iter()
+            .map(|scalars| scalars.clone().into_par_iter().map(|s| g.mul(s).into()).collect())
+            .collect();
+
+        let V: Vec<Vec<_>> = tau.iter()
+            .map(|tau| tau.iter().map(|t| v.mul(t).into()).collect())
+            .collect();
+
+        KZHkSRS {
+            degrees,
+            H,
+            V,
+            v,
+        }
+    }
+
+    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> (Self::Commitment, Self::CommitmentHint) {
+        assert!(srs.has_dimensions(K), "the srs is not split into K dimensions");
+        let len = srs.num_variables();
+        assert!(poly.num_variables <= len, "polynomial has more variables than the srs supports");
+        let poly = poly.extend_number_of_variables(len);
+
+        assert_eq!(poly.num_variables, len);
+        assert_eq!(poly.len, 1 << poly.num_variables);
+        assert_eq!(poly.evaluation_over_boolean_hypercube.len(), poly.len);
+
+        let C = E::G1::msm(&srs.H[0], &poly.evaluation_over_boolean_hypercube).unwrap().into();
+
+        let D_x = (0..srs.degrees[0])
+            .into_par_iter()
+            .map(|i| {
+                E::G1::msm_unchecked(
+                    srs.H[1].as_slice(),
+                    poly.get_partial_evaluation_for_boolean_input(i, srs.suffix_size(1)).as_slice(),
+                )
+            }).collect::<Vec<_>>();
+
+        (KZHkCommitment { C }, KZHkCommitmentHint { D_x })
+    }
+
+    fn open(
+        srs: &Self::SRS,
+        input: &[E::ScalarField],
+        hint: &Self::CommitmentHint,
+        poly: &MultilinearPolynomial<E::ScalarField>,
+    ) -> Self::Opening {
+        const { assert!(K >= 2, "KZHk needs at least two dimensions") };
+        assert!(srs.has_dimensions(K), "the srs is not split into K dimensions");
+        let len = srs.num_variables();
+        let poly = poly.extend_number_of_variables(len);
+
+        assert_eq!(poly.num_variables, len);
+        assert_eq!(poly.len, 1 << poly.num_variables);
+        assert_eq!(poly.evaluation_over_boolean_hypercube.len(), poly.len);
+
+        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+
+        // at each level bind one more dimension and commit to the slices of the next one
+        let mut partial = poly;
+        let mut D = Vec::new();
+        for j in 1..K - 1 {
+            partial = partial.partial_evaluation(split_input[j - 1].as_slice());
+            D.push((0..srs.degrees[j])
+                .into_par_iter()
+                .map(|i| {
+                    E::G1::msm_unchecked(
+                        srs.H[j + 1].as_slice(),
+                        partial.get_partial_evaluation_for_boolean_input(i, srs.suffix_size(j + 1)).as_slice(),
+                    )
+                }).collect::<Vec<_>>());
+        }
+
+        // compute the partial evaluation of the polynomial on all but the last dimension
+        let f_star = partial.partial_evaluation(split_input[K - 2].as_slice());
+
+        KZHkOpening {
+            D_x: hint.D_x.clone(),
+            D,
+            f_star,
+        }
+    }
+
+    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) -> Result<(), PCSError> {
+        const { assert!(K >= 2, "KZHk needs at least two dimensions") };
+        if !srs.has_dimensions(K) {
+            return Err(PCSError::InvalidSRS);
+        }
+
+        let split_input = Self::split_input(&srs, input, E::ScalarField::ZERO);
+
+        // making sure D_x is well-formatted
+        if open.D_x.len() != srs.degrees[0]
+            || E::multi_pairing(&open.D_x, &srs.V[0]).0 != E::pairing(com.C, &srs.v).0 {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        // the commitment to f(r_0, .) w.r.t. H[1]
+        let mut C = E::G1::msm(
+            &open.D_x.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+            EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
+
+        // making sure each level of D is consistent with the previous one
+        if open.D.len() != K - 2 {
+            return Err(PCSError::PairingCheckFailed);
+        }
+        for j in 1..K - 1 {
+            let D = &open.D[j - 1];
+            if D.len() != srs.degrees[j]
+                || E::multi_pairing(D, &srs.V[j]).0 != E::pairing(C, &srs.v).0 {
+                return Err(PCSError::PairingCheckFailed);
+            }
+
+            C = E::G1::msm(
+                &D.iter().map(|e| e.clone().into()).collect::<Vec<_>>().as_slice(),
+                EqPolynomial::new(split_input[j].clone()).evals().as_slice(),
+            ).map_err(|_| PCSError::MSMCheckFailed)?;
+        }
+
+        // making sure f^star is well formatted
+        let lhs = E::G1::msm(
+            srs.H[K - 1].as_slice(),
+            open.f_star.evaluation_over_boolean_hypercube.as_slice(),
+        ).map_err(|_| PCSError::MSMCheckFailed)?;
+
+        if lhs != C {
+            return Err(PCSError::MSMCheckFailed);
+        }
+
+        // making sure the output of f_star and the given output are consistent
+        if open.f_star.num_variables != split_input[K - 1].len()
+            || open.f_star.evaluate(split_input[K - 1].as_slice()) != *output {
+            return Err(PCSError::EvaluationCheckFailed);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing, F: PrimeField + Absorb> AppendToTranscript<F> for KZHkCommitment<E>
+where
+    E: Pairing<ScalarField=F>,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    fn append_to_transcript(&self, label: &'static [u8], transcript: &mut Transcript<F>) {
+        Transcript::append_point::<E>(transcript, label, &self.C);
+    }
+}
+
+impl<E: Pairing> ToAffine<E> for KZHkCommitment<E> {
+    fn to_affine(self) -> E::G1Affine {
+        self.C
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZHkCommitment<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZHkCommitment { C: (self.C + other.C).into_affine() }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZHkCommitment { C: self.C.mul(r).into_affine() }
+    }
+
+    fn linear_combination(terms: &[(E::ScalarField, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        // accumulate in projective form to normalize only once
+        let C = terms.iter().fold(E::G1::ZERO, |acc, (coeff, com)| acc + com.C.mul(coeff));
+        KZHkCommitment { C: C.into_affine() }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZHkCommitmentHint<E> {
+    fn add(&self, other: &Self) -> Self {
+        KZHkCommitmentHint {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZHkCommitmentHint {
+            D_x: scale_vector(&self.D_x, r),
+        }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for KZHkOpening<E> {
+    fn add(&self, other: &Self) -> Self {
+        assert_eq!(self.D.len(), other.D.len(), "openings must have the same number of levels");
+
+        KZHkOpening {
+            D_x: add_vectors(&self.D_x, &other.D_x),
+            D: self.D.iter().zip(other.D.iter()).map(|(a, b)| add_vectors(a, b)).collect(),
+            f_star: self.f_star.add(&other.f_star),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        KZHkOpening {
+            D_x: scale_vector(&self.D_x, r),
+            D: self.D.iter().map(|d| scale_vector(d, r)).collect(),
+            f_star: self.f_star.scale(r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use rand::thread_rng;
+
+    fn pcs_test_helper<const K: usize>(num_vars: usize) {
+        let input: Vec<F> = (0..num_vars)
+            .map(|_| F::rand(&mut thread_rng()))
+            .collect();
+
+        // build the srs
+        let srs: KZHkSRS<E> = KZHk::<E, K>::setup(num_vars, &mut thread_rng());
+        assert_eq!(srs.degrees.len(), K);
+        assert_eq!(srs.num_variables(), num_vars);
+
+        // build a random polynomials
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+
+        // evaluate polynomial
+        let eval = polynomial.evaluate(input.as_slice());
+
+        // commit to the polynomial
+        let (com, hint) = KZHk::<E, K>::commit(&srs, &polynomial);
+
+        // open it
+        let open = KZHk::<E, K>::open(&srs, input.as_slice(), &hint, &polynomial);
+
+        // verify the commit
+        KZHk::<E, K>::verify(&srs, input.as_slice(), &eval, &com, &open).unwrap();
+
+        // a wrong output must be rejected
+        assert_eq!(
+            KZHk::<E, K>::verify(&srs, input.as_slice(), &(eval + F::from(1u64)), &com, &open),
+            Err(PCSError::EvaluationCheckFailed)
+        );
+    }
+
+    #[test]
+    fn pcs_test() {
+        pcs_test_helper::<2>(8);
+        pcs_test_helper::<3>(8);
+        pcs_test_helper::<4>(10);
+        pcs_test_helper::<5>(10);
+        pcs_test_helper::<6>(12);
+    }
+
+    #[test]
+    fn test_small_polynomial() {
+        let num_vars = 10;
+        let srs: KZHkSRS<E> = KZHk::<E, 5>::setup(num_vars, &mut thread_rng());
+
+        // a polynomial with fewer variables than the srs is padded
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(7, &mut thread_rng());
+        let input: Vec<F> = (0..7).map(|_| F::rand(&mut thread_rng())).collect();
+        let eval = polynomial.evaluate(input.as_slice());
+
+        let (com, hint) = KZHk::<E, 5>::commit(&srs, &polynomial);
+        let open = KZHk::<E, 5>::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZHk::<E, 5>::verify(&srs, input.as_slice(), &eval, &com, &open).unwrap();
+    }
+
+    fn test_open_boolean_helper<const K: usize>(num_vars: usize) {
+        let srs: KZHkSRS<E> = KZHk::<E, K>::setup(num_vars, &mut thread_rng());
+
+        for num_variables in [num_vars, num_vars - 3] {
+            let polynomial = MultilinearPolynomial::rand(num_variables, &mut thread_rng());
+            let (com, hint) = KZHk::<E, K>::commit(&srs, &polynomial);
+
+            for index in [0, 5, (1 << num_variables) - 1] {
+                let output = polynomial.evaluation_over_boolean_hypercube[index];
+                let open = KZHk::<E, K>::open_boolean(&srs, index, &hint, &polynomial);
+                KZHk::<E, K>::verify_boolean(&srs, index, &output, &com, &open).unwrap();
+
+                assert_eq!(
+                    KZHk::<E, K>::verify_boolean(&srs, index, &(output + F::from(1u64)), &com, &open),
+                    Err(PCSError::EvaluationCheckFailed)
+                );
+            }
+
+            let open = KZHk::<E, K>::open_boolean(&srs, 0, &hint, &polynomial);
+            assert_eq!(
+                KZHk::<E, K>::verify_boolean(&srs, 1 << num_vars, &F::ZERO, &com, &open),
+                Err(PCSError::IndexOutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn test_open_boolean() {
+        test_open_boolean_helper::<2>(8);
+        test_open_boolean_helper::<3>(8);
+        test_open_boolean_helper::<4>(8);
+        test_open_boolean_helper::<5>(10);
+    }
+
+    #[test]
+    fn test_setup_with_degrees() {
+        let srs: KZHkSRS<E> = KZHk::<E, 3>::setup_with_degrees(vec![1, 2, 5], &mut thread_rng());
+        assert_eq!(srs.degrees, vec![2, 4, 32]);
+
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+
+        let (com, hint) = KZHk::<E, 3>::commit(&srs, &polynomial);
+        let open = KZHk::<E, 3>::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZHk::<E, 3>::verify(&srs, input.as_slice(), &polynomial.evaluate(&input), &com, &open).unwrap();
+    }
+
+    #[test]
+    fn test_srs_of_another_dimension() {
+        let srs: KZHkSRS<E> = KZHk::<E, 2>::setup(8, &mut thread_rng());
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(8, &mut thread_rng());
+        let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+        let output = polynomial.evaluate(&input);
+
+        let (com, hint) = KZHk::<E, 2>::commit(&srs, &polynomial);
+        let open = KZHk::<E, 2>::open(&srs, input.as_slice(), &hint, &polynomial);
+        KZHk::<E, 2>::verify(&srs, input.as_slice(), &output, &com, &open).unwrap();
+
+        // the srs is rejected by the schemes with another number of dimensions
+        assert_eq!(KZHk::<E, 3>::verify(&srs, input.as_slice(), &output, &com, &open), Err(PCSError::InvalidSRS));
+        assert_eq!(KZHk::<E, 4>::verify(&srs, input.as_slice(), &output, &com, &open), Err(PCSError::InvalidSRS));
+
+        // as well as an srs whose elements don't match its degrees
+        let mut truncated = srs.clone();
+        truncated.V[0].pop();
+        assert_eq!(KZHk::<E, 2>::verify(&truncated, input.as_slice(), &output, &com, &open), Err(PCSError::InvalidSRS));
+    }
+
+    fn test_batch_open_helper<const K: usize>(num_vars: usize) {
+        let srs: KZHkSRS<E> = KZHk::<E, K>::setup(num_vars, &mut thread_rng());
+
+        let polys: Vec<MultilinearPolynomial<F>> = (0..3)
+            .map(|_| MultilinearPolynomial::rand(num_vars, &mut thread_rng()))
+            .collect();
+        let (coms, hints): (Vec<_>, Vec<_>) = polys.iter().map(|poly| KZHk::<E, K>::commit(&srs, poly)).unzip();
+
+        let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+        let outputs: Vec<_> = polys.iter().map(|poly| poly.evaluate(&input)).collect();
+
+        let mut prover_transcript = Transcript::new(b"batch");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let open = KZHk::<E, K>::batch_open(&srs, input.as_slice(), coms.as_slice(), hints.as_slice(), polys.as_slice(), &mut prover_transcript);
+        KZHk::<E, K>::verify_batch_open(&srs, input.as_slice(), outputs.as_slice(), coms.as_slice(), &open, &mut verifier_transcript).unwrap();
+
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[1] += F::from(1u64);
+        assert!(KZHk::<E, K>::verify_batch_open(&srs, input.as_slice(), wrong_outputs.as_slice(), coms.as_slice(), &open, &mut Transcript::new(b"batch")).is_err());
+    }
+
+    #[test]
+    fn test_batch_open() {
+        test_batch_open_helper::<2>(8);
+        test_batch_open_helper::<3>(8);
+        test_batch_open_helper::<4>(8);
+        test_batch_open_helper::<5>(10);
+    }
+}
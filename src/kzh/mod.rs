@@ -1,11 +1,23 @@
 use std::fmt::Debug;
+use std::sync::OnceLock;
 use ark_crypto_primitives::sponge::Absorb;
 use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
 use ark_ec::pairing::Pairing;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, PrimeField};
+use ark_std::UniformRand;
+use rayon::prelude::*;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate, Write};
 use rand::Rng;
 use crate::nexus_spartan::commitment_traits::ToAffine;
-use crate::transcript::transcript::AppendToTranscript;
+use crate::transcript::transcript::{AppendToTranscript, Transcript};
+use crate::kzh::errors::{PCSError, SRSError};
+use crate::utils::compute_powers;
+use crate::math::Math;
+
+pub mod ceremony;
+
+pub mod errors;
 
 pub mod kzh2;
 
@@ -13,9 +25,20 @@ pub mod kzh3;
 
 pub mod kzh4;
 
+pub mod kzhk;
+
+pub mod multi_point;
+
+pub mod poly_commit;
+
+pub mod pst13;
+
+pub mod srs_import;
+
 pub trait KZH<E: Pairing> where <E as Pairing>::ScalarField: Absorb {
     type Degree;
     type SRS: CanonicalSerialize + CanonicalDeserialize + Clone;
+    /// The succinct commitment, this is all the verifier gets
     type Commitment: AppendToTranscript<E::ScalarField>
     + Debug
     + CanonicalSerialize
@@ -24,24 +47,41 @@ pub trait KZH<E: Pairing> where <E as Pairing>::ScalarField: Absorb {
     + Eq
     + Clone
     + AppendToTranscript<E::ScalarField>
-    + ToAffine<E>;
-    type Opening: Sync + CanonicalSerialize + CanonicalDeserialize + Debug;
+    + ToAffine<E>
+    + Homomorphic<E::ScalarField>;
+    /// Prover-only data output by commit alongside the commitment, needed to open but never sent to the verifier
+    type CommitmentHint: Sync
+    + CanonicalSerialize
+    + CanonicalDeserialize
+    + Debug
+    + PartialEq
+    + Eq
+    + Clone
+    + Homomorphic<E::ScalarField>;
+    type Opening: Sync + CanonicalSerialize + CanonicalDeserialize + Debug + Clone + Homomorphic<E::ScalarField>;
 
     fn split_input<T: Clone>(srs: &Self::SRS, input: &[T], default: T) -> Vec<Vec<T>>;
 
     fn get_degree_from_maximum_supported_degree(n: usize) -> Self::Degree;
 
-    fn setup<R: Rng>(maximum_degree: usize, rng: &mut R) -> Self::SRS;
+    /// Generates an SRS for polynomials with `maximum_degree` variables, split evenly between the dimensions
+    fn setup<R: Rng>(maximum_degree: usize, rng: &mut R) -> Self::SRS {
+        Self::setup_with_degrees(Self::get_degree_from_maximum_supported_degree(maximum_degree), rng)
+    }
+
+    /// Generates an SRS for an explicit split of the variables, `degrees` holds the number of variables
+    /// of each dimension (the log of its degree), e.g. a small degree_x makes the verifier cheaper
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS;
 
     fn commit(
         srs: &Self::SRS,
         poly: &MultilinearPolynomial<E::ScalarField>,
-    ) -> Self::Commitment;
+    ) -> (Self::Commitment, Self::CommitmentHint);
 
     fn open(
         srs: &Self::SRS,
         input: &[E::ScalarField],
-        com: &Self::Commitment,
+        hint: &Self::CommitmentHint,
         poly: &MultilinearPolynomial<E::ScalarField>,
     ) -> Self::Opening;
 
@@ -51,5 +91,504 @@ pub trait KZH<E: Pairing> where <E as Pairing>::ScalarField: Absorb {
         output: &E::ScalarField,
         com: &Self::Commitment,
         open: &Self::Opening,
-    );
+    ) -> Result<(), PCSError>;
+
+    /// Opens the commitment at the boolean point given by the bits of `index`, most significant bit
+    /// first, i.e. at the index-th evaluation of the polynomial. By default this is a regular opening
+    /// at that point, schemes override it when boolean points allow a cheaper opening
+    fn open_boolean(
+        srs: &Self::SRS,
+        index: usize,
+        hint: &Self::CommitmentHint,
+        poly: &MultilinearPolynomial<E::ScalarField>,
+    ) -> Self::Opening {
+        let input = boolean_point(index, Self::num_variables(srs));
+        Self::open(srs, input.as_slice(), hint, poly)
+    }
+
+    /// Verifies an opening produced by open_boolean
+    fn verify_boolean(
+        srs: &Self::SRS,
+        index: usize,
+        output: &E::ScalarField,
+        com: &Self::Commitment,
+        open: &Self::Opening,
+    ) -> Result<(), PCSError> {
+        let num_variables = Self::num_variables(srs);
+        if index >= 1 << num_variables {
+            return Err(PCSError::IndexOutOfRange);
+        }
+
+        let input = boolean_point(index, num_variables);
+        Self::verify(srs, input.as_slice(), output, com, open)
+    }
+
+    /// Returns the number of variables supported by the srs
+    fn num_variables(srs: &Self::SRS) -> usize {
+        // the empty input gets padded to the full number of variables
+        Self::split_input(srs, &[], false).iter().map(|part| part.len()).sum()
+    }
+
+    /// Opens several committed polynomials at the same point with a single opening, the polynomials
+    /// are combined with the powers of a transcript challenge and the combination is opened
+    fn batch_open(
+        srs: &Self::SRS,
+        input: &[E::ScalarField],
+        coms: &[Self::Commitment],
+        hints: &[Self::CommitmentHint],
+        polys: &[MultilinearPolynomial<E::ScalarField>],
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Self::Opening {
+        assert!(!polys.is_empty(), "there should be at least one polynomial");
+        assert_eq!(coms.len(), polys.len(), "number of commitments and polynomials must match");
+        assert_eq!(hints.len(), polys.len(), "number of hints and polynomials must match");
+
+        let len = Self::num_variables(srs);
+        let padded_input = Self::split_input(srs, input, E::ScalarField::ZERO).concat();
+
+        // compute the evaluations so that the challenge is bound to them
+        let outputs: Vec<_> = polys.iter()
+            .map(|poly| poly.extend_number_of_variables(len).evaluate(padded_input.as_slice()))
+            .collect();
+
+        let coeffs = get_batching_challenges::<E, _>(transcript, input, outputs.as_slice(), coms);
+
+        // the combined polynomial and its hint
+        let poly = combine_polynomials(polys, coeffs.as_slice(), len);
+        let terms: Vec<_> = coeffs.iter().cloned().zip(hints.iter()).collect();
+        let hint = <Self::CommitmentHint as Homomorphic<E::ScalarField>>::linear_combination(terms.as_slice());
+
+        Self::open(srs, input, &hint, &poly)
+    }
+
+    /// Verifies an opening produced by batch_open against the commitments and the claimed evaluations
+    fn verify_batch_open(
+        srs: &Self::SRS,
+        input: &[E::ScalarField],
+        outputs: &[E::ScalarField],
+        coms: &[Self::Commitment],
+        open: &Self::Opening,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Result<(), PCSError> {
+        if coms.is_empty() || outputs.len() != coms.len() {
+            return Err(PCSError::InvalidBatchSize);
+        }
+
+        let coeffs = get_batching_challenges::<E, _>(transcript, input, outputs, coms);
+
+        let terms: Vec<_> = coeffs.iter().cloned().zip(coms.iter()).collect();
+        let com = <Self::Commitment as Homomorphic<E::ScalarField>>::linear_combination(terms.as_slice());
+        let output: E::ScalarField = outputs.iter()
+            .zip(coeffs.iter())
+            .map(|(output, coeff)| *output * coeff)
+            .sum();
+
+        Self::verify(srs, input, &output, &com, open)
+    }
+}
+
+/// Opening size and verifier cost of a KZH scheme for a given split of the variables, used to pick a
+/// split, e.g. a small first dimension for a cheap in-circuit verifier
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostReport {
+    /// number of variables of each dimension
+    pub num_variables: Vec<usize>,
+    /// number of G1 elements in an opening
+    pub proof_group_elements: usize,
+    /// number of field elements in an opening
+    pub proof_field_elements: usize,
+    /// size in bytes of the group and field elements of an opening, compressed
+    pub proof_size: usize,
+    /// total number of bases in the MSMs run by the verifier
+    pub verifier_msm_size: usize,
+    /// number of pairings computed by the verifier
+    pub verifier_pairings: usize,
+}
+
+impl CostReport {
+    pub fn new<E: Pairing>(
+        num_variables: Vec<usize>,
+        proof_group_elements: usize,
+        proof_field_elements: usize,
+        verifier_msm_size: usize,
+        verifier_pairings: usize,
+    ) -> CostReport {
+        let proof_size = proof_group_elements * E::G1Affine::generator().compressed_size()
+            + proof_field_elements * E::ScalarField::ZERO.compressed_size();
+
+        CostReport {
+            num_variables,
+            proof_group_elements,
+            proof_field_elements,
+            proof_size,
+            verifier_msm_size,
+            verifier_pairings,
+        }
+    }
+}
+
+/// Commitments, hints and openings of the KZH schemes are linear in the committed polynomial, so they
+/// can be combined like the polynomials themselves: combining the commitments (resp. the openings at
+/// the same point) of f_1, ..., f_n gives the commitment (resp. the opening) of sum_i c_i * f_i
+pub trait Homomorphic<F: PrimeField>: Sized {
+    /// Returns self + other
+    fn add(&self, other: &Self) -> Self;
+
+    /// Returns r * self
+    fn scale(&self, r: &F) -> Self;
+
+    /// Returns sum_i c_i * x_i for terms (c_i, x_i)
+    fn linear_combination(terms: &[(F, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        terms.iter()
+            .map(|(coeff, x)| x.scale(coeff))
+            .reduce(|acc, x| acc.add(&x))
+            .unwrap()
+    }
+}
+
+impl<F: PrimeField> Homomorphic<F> for MultilinearPolynomial<F> {
+    fn add(&self, other: &Self) -> Self {
+        self.clone() + other.clone()
+    }
+
+    fn scale(&self, r: &F) -> Self {
+        let mut poly = self.clone();
+        poly.scalar_mul(r);
+        poly
+    }
+}
+
+/// Returns a + b element-wise
+pub(crate) fn add_vectors<G: CurveGroup>(a: &[G], b: &[G]) -> Vec<G> {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    a.iter().zip(b.iter()).map(|(a, b)| *a + b).collect()
+}
+
+/// Returns r * a element-wise
+pub(crate) fn scale_vector<G: CurveGroup>(a: &[G], r: &G::ScalarField) -> Vec<G> {
+    a.iter().map(|a| *a * r).collect()
+}
+
+/// Returns n scalars for batch verification, the first one is 1 and the others are sampled from
+/// 128-bit strings, which is enough for soundness, the same way as `KZG10::batch_check`
+pub(crate) fn get_batch_randomizers<F: PrimeField, R: Rng>(n: usize, rng: &mut R) -> Vec<F> {
+    (0..n)
+        .map(|k| if k == 0 { F::one() } else { u128::rand(rng).into() })
+        .collect()
+}
+
+/// Returns sum_k randomizers[k] * vectors[k] element-wise, every element is an MSM over the batch
+pub(crate) fn combine_columns<G: CurveGroup>(vectors: &[&[G::Affine]], randomizers: &[G::ScalarField], len: usize) -> Vec<G> {
+    assert_eq!(vectors.len(), randomizers.len(), "number of vectors and randomizers must match");
+
+    (0..len)
+        .into_par_iter()
+        .map(|i| {
+            let bases: Vec<_> = vectors.iter().map(|v| v[i]).collect();
+            G::msm_unchecked(bases.as_slice(), randomizers)
+        })
+        .collect()
+}
+
+/// Returns the indices for which `check` fails, where `check` on a set of indices succeeds if and only
+/// if it succeeds on each of them. A set that passes is discarded as a whole and one that fails is
+/// split in half, so when few items are invalid this takes far fewer checks than one per item
+pub(crate) fn bisect_failures<C: FnMut(&[usize]) -> bool>(indices: &[usize], check: &mut C) -> Vec<usize> {
+    if indices.is_empty() || check(indices) {
+        return Vec::new();
+    }
+    if indices.len() == 1 {
+        return indices.to_vec();
+    }
+
+    let (left, right) = indices.split_at(indices.len() / 2);
+    let mut invalid = bisect_failures(left, check);
+    invalid.extend(bisect_failures(right, check));
+    invalid
+}
+
+/// Returns the boolean point whose coordinates are the bits of `index`, most significant bit first,
+/// so that evaluating a polynomial there gives its index-th evaluation over the boolean hypercube
+pub fn boolean_point<F: PrimeField>(index: usize, num_variables: usize) -> Vec<F> {
+    assert!(index < 1 << num_variables, "index out of range");
+
+    index.get_bits_canonical_order(num_variables)
+        .into_iter()
+        .map(|bit| if bit { F::one() } else { F::zero() })
+        .collect()
+}
+
+/// Binds the commitments, the evaluation point and the claimed evaluations to the transcript and
+/// returns [1, r, ..., r^(n-1)] for a challenge r, used to batch n openings at the same point
+pub(crate) fn get_batching_challenges<E: Pairing, C: AppendToTranscript<E::ScalarField>>(
+    transcript: &mut Transcript<E::ScalarField>,
+    input: &[E::ScalarField],
+    outputs: &[E::ScalarField],
+    coms: &[C],
+) -> Vec<E::ScalarField>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    assert_eq!(outputs.len(), coms.len(), "number of evaluations and commitments must match");
+
+    for com in coms {
+        com.append_to_transcript(b"batch commitment", transcript);
+    }
+    transcript.append_scalars(b"batch point", input);
+    transcript.append_scalars(b"batch evaluations", outputs);
+
+    let r = transcript.challenge_scalar(b"batch challenge");
+    compute_powers(&r, coms.len())
+}
+
+/// Returns sum_i coeffs[i] * polys[i], all polynomials are first extended to num_variables
+pub(crate) fn combine_polynomials<F: PrimeField>(
+    polys: &[MultilinearPolynomial<F>],
+    coeffs: &[F],
+    num_variables: usize,
+) -> MultilinearPolynomial<F> {
+    assert_eq!(polys.len(), coeffs.len(), "number of polynomials and coefficients must match");
+
+    let mut evals = vec![F::ZERO; 1 << num_variables];
+    for (poly, coeff) in polys.iter().zip(coeffs.iter()) {
+        let poly = poly.extend_number_of_variables(num_variables);
+        evals.par_iter_mut()
+            .zip(poly.evaluation_over_boolean_hypercube.par_iter())
+            .for_each(|(e, p)| *e += *coeff * p);
+    }
+
+    MultilinearPolynomial::new(evals)
+}
+
+/// Returns a ⊗ b, i.e. [a_0 * b_0, a_0 * b_1, ..., a_1 * b_0, ...]
+pub(crate) fn tensor<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    a.iter().flat_map(|a| b.iter().map(move |b| *a * b)).collect()
+}
+
+/// Checks upper[i * lower.len() + l] = tau_i * lower[l] where V[i] = tau_i * v, with the single
+/// randomized check e(sum_{i,l} r_i u_l upper[i, l], v) = e(sum_l u_l lower[l], sum_i r_i V[i])
+pub(crate) fn check_level<E: Pairing, R: Rng>(
+    upper: &[E::G1Affine],
+    lower: &[E::G1Affine],
+    V: &[E::G2Affine],
+    v: &E::G2Affine,
+    rng: &mut R,
+) -> bool {
+    if upper.len() != lower.len() * V.len() {
+        return false;
+    }
+
+    let r: Vec<E::ScalarField> = (0..V.len()).map(|_| E::ScalarField::rand(rng)).collect();
+    let u: Vec<E::ScalarField> = (0..lower.len()).map(|_| E::ScalarField::rand(rng)).collect();
+
+    let lhs = E::G1::msm_unchecked(upper, tensor(r.as_slice(), u.as_slice()).as_slice());
+    let rhs_g1 = E::G1::msm_unchecked(lower, u.as_slice());
+    let rhs_g2 = E::G2::msm_unchecked(V, r.as_slice());
+
+    E::multi_pairing([lhs, -rhs_g1], [v.into_group(), rhs_g2]).0.is_one()
+}
+
+/// Checks H[k] = tau_k * g and V[k] = tau_k * v for the same tau, i.e. e(H[k], V[l]) = e(H[l], V[k]),
+/// with the single randomized check e(sum_k r_k H[k], sum_l s_l V[l]) = e(sum_k s_k H[k], sum_l r_l V[l])
+pub(crate) fn check_proportional<E: Pairing, R: Rng>(
+    H: &[E::G1Affine],
+    V: &[E::G2Affine],
+    rng: &mut R,
+) -> bool {
+    if H.len() != V.len() {
+        return false;
+    }
+
+    let r: Vec<E::ScalarField> = (0..H.len()).map(|_| E::ScalarField::rand(rng)).collect();
+    let s: Vec<E::ScalarField> = (0..H.len()).map(|_| E::ScalarField::rand(rng)).collect();
+
+    let (H_r, H_s) = (E::G1::msm_unchecked(H, r.as_slice()), E::G1::msm_unchecked(H, s.as_slice()));
+    let (V_r, V_s) = (E::G2::msm_unchecked(V, r.as_slice()), E::G2::msm_unchecked(V, s.as_slice()));
+
+    E::multi_pairing([H_r, -H_s], [V_s, V_r]).0.is_one()
+}
+
+/// A value derived from an SRS and computed on its first use, e.g. the prepared verifier key. It is
+/// not serialized and clones of the SRS start without it, so that a deserialized or modified copy
+/// derives it again, but it is not updated if the SRS it belongs to is modified in place
+pub struct SRSCache<T>(OnceLock<T>);
+
+impl<T> SRSCache<T> {
+    /// Returns the cached value, computing it with `init` on the first call
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        self.0.get_or_init(init)
+    }
+}
+
+impl<T> Default for SRSCache<T> {
+    fn default() -> Self {
+        SRSCache(OnceLock::new())
+    }
+}
+
+impl<T> Clone for SRSCache<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> Debug for SRSCache<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SRSCache")
+    }
+}
+
+/// The cached value is derived from the rest of the SRS, so it never makes two SRSs different
+impl<T> PartialEq for SRSCache<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for SRSCache<T> {}
+
+impl<T> CanonicalSerialize for SRSCache<T> {
+    fn serialize_with_mode<W: Write>(&self, _writer: W, _compress: Compress) -> Result<(), SerializationError> {
+        Ok(())
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        0
+    }
+}
+
+impl<T: Send + Sync> Valid for SRSCache<T> {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl<T: Send + Sync> CanonicalDeserialize for SRSCache<T> {
+    fn deserialize_with_mode<R: Read>(_reader: R, _compress: Compress, _validate: Validate) -> Result<Self, SerializationError> {
+        Ok(Self::default())
+    }
+}
+
+/// Prepends zeros to the input if it is shorter than sum_i log(degrees[i]) and splits it into one
+/// part per dimension, the i-th part having log(degrees[i]) entries
+pub(crate) fn split_input_by_degrees<T: Clone>(degrees: &[usize], input: &[T], default: T) -> Vec<Vec<T>> {
+    let total_length: usize = degrees.iter().map(|degree| degree.log_2()).sum();
+
+    // If r is smaller than the required length, extend it with zeros at the beginning
+    let mut extended_r = input.to_vec();
+    if input.len() < total_length {
+        let mut zeros = vec![default; total_length - input.len()];
+        zeros.extend(extended_r);  // Prepend zeros to the beginning
+        extended_r = zeros;
+    }
+
+    let mut start = 0;
+    degrees.iter()
+        .map(|degree| {
+            let part = extended_r[start..start + degree.log_2()].to_vec();
+            start += degree.log_2();
+            part
+        })
+        .collect()
+}
+
+/// Splits maximum_degree variables over the dimensions of an SRS whose dimensions have `available`
+/// variables, it's the `balanced` split if it fits, otherwise the variables of the SRS are removed
+/// one at a time from the last of its largest dimensions. Returns None if the SRS has fewer variables
+pub(crate) fn trimmed_degrees(balanced: &[usize], available: &[usize], maximum_degree: usize) -> Option<Vec<usize>> {
+    if available.iter().sum::<usize>() < maximum_degree {
+        return None;
+    }
+    if balanced.iter().zip(available).all(|(degree, available)| degree <= available) {
+        return Some(balanced.to_vec());
+    }
+
+    let mut degrees = available.to_vec();
+    while degrees.iter().sum::<usize>() > maximum_degree {
+        // max_by_key returns the last maximum
+        let largest = (0..degrees.len()).max_by_key(|&i| degrees[i]).unwrap();
+        degrees[largest] -= 1;
+    }
+    Some(degrees)
+}
+
+/// Returns the degree 2^log_degree of a dimension of a trimmed SRS, if it fits in the `available`
+/// degree of the dimension of the original SRS
+pub(crate) fn trimmed_degree(log_degree: usize, available: usize) -> Result<usize, SRSError> {
+    u32::try_from(log_degree).ok()
+        .and_then(|shift| 1usize.checked_shl(shift))
+        .filter(|degree| *degree <= available)
+        .ok_or(SRSError::TrimmedDegreeTooLarge)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::kzh2::KZH2;
+    use crate::kzh::kzh3::KZH3;
+    use crate::kzh::kzh4::KZH4;
+    use crate::kzh::kzhk::KZHk;
+    use crate::kzh::pst13::PST13;
+    use crate::kzh::errors::SRSError;
+    use crate::kzh::{trimmed_degree, trimmed_degrees, Homomorphic, KZH};
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+
+    fn test_linear_combination_helper<PC: KZH<E>>(num_vars: usize) {
+        let srs = PC::setup(num_vars, &mut thread_rng());
+
+        let f = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let g = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let (a, b) = (F::rand(&mut thread_rng()), F::rand(&mut thread_rng()));
+
+        let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+        let output = a * f.evaluate(&input) + b * g.evaluate(&input);
+
+        let (com_f, hint_f) = PC::commit(&srs, &f);
+        let (com_g, hint_g) = PC::commit(&srs, &g);
+        let open_f = PC::open(&srs, input.as_slice(), &hint_f, &f);
+        let open_g = PC::open(&srs, input.as_slice(), &hint_g, &g);
+
+        // the combined commitment is the commitment to a * f + b * g
+        let com = PC::Commitment::linear_combination(&[(a, &com_f), (b, &com_g)]);
+        let hint = PC::CommitmentHint::linear_combination(&[(a, &hint_f), (b, &hint_g)]);
+        let h = Homomorphic::add(&f.scale(&a), &g.scale(&b));
+        assert_eq!((com.clone(), hint), PC::commit(&srs, &h));
+        assert_eq!(com, Homomorphic::add(&com_f.scale(&a), &com_g.scale(&b)));
+
+        // and the combined opening verifies against it
+        let open = PC::Opening::linear_combination(&[(a, &open_f), (b, &open_g)]);
+        PC::verify(&srs, input.as_slice(), &output, &com, &open).unwrap();
+        assert!(PC::verify(&srs, input.as_slice(), &(output + F::from(1u64)), &com, &open).is_err());
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        test_linear_combination_helper::<KZH2<E>>(8);
+        test_linear_combination_helper::<KZH3<E>>(9);
+        test_linear_combination_helper::<KZH4<E>>(8);
+        test_linear_combination_helper::<KZHk<E, 2>>(8);
+        test_linear_combination_helper::<KZHk<E, 3>>(9);
+        test_linear_combination_helper::<KZHk<E, 4>>(8);
+        test_linear_combination_helper::<KZHk<E, 5>>(10);
+        test_linear_combination_helper::<PST13<E>>(8);
+    }
+
+    #[test]
+    fn test_trimmed_degrees() {
+        // the balanced split is kept whenever it fits
+        assert_eq!(trimmed_degrees(&[3, 3], &[5, 5], 6), Some(vec![3, 3]));
+        // otherwise the largest dimensions are reduced
+        assert_eq!(trimmed_degrees(&[3, 3], &[2, 8], 6), Some(vec![2, 4]));
+        assert_eq!(trimmed_degrees(&[2, 2, 1], &[1, 4, 4], 5), Some(vec![1, 2, 2]));
+        assert_eq!(trimmed_degrees(&[3, 3], &[2, 3], 6), None);
+
+        assert!(matches!(trimmed_degree(3, 16), Ok(8)));
+        assert!(matches!(trimmed_degree(5, 16), Err(SRSError::TrimmedDegreeTooLarge)));
+        assert!(matches!(trimmed_degree(usize::MAX, usize::MAX), Err(SRSError::TrimmedDegreeTooLarge)));
+    }
 }
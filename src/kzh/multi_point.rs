@@ -0,0 +1,190 @@
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::kzh::errors::PCSError;
+use crate::kzh::{get_batching_challenges, KZH};
+use crate::nexus_spartan::sumcheck::SumcheckInstanceProof;
+use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
+use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+use crate::transcript::transcript::Transcript;
+
+/// Opening of several committed polynomials, each at its own point, with a single PCS opening.
+///
+/// Given claims f_i(x_i) = y_i and a challenge r, the prover runs a sumcheck on
+/// sum_b sum_i r^i * eq(x_i, b) * f_i(b) = sum_i r^i * y_i which reduces all the claims to
+/// evaluations f_i(rho) at a common random point rho, those are then opened with `batch_open`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+pub struct MultiPointOpening<E: Pairing<ScalarField=F>, PC: KZH<E>, F: PrimeField + Absorb> {
+    /// Sumcheck proof for sum_b sum_i r^i * eq(x_i, b) * f_i(b)
+    pub sumcheck_proof: SumcheckInstanceProof<F>,
+    /// The evaluations f_i(rho) at the point rho output by the sumcheck
+    pub evaluations: Vec<F>,
+    /// A single opening proof of all f_i at rho
+    pub opening: PC::Opening,
+}
+
+impl<E: Pairing<ScalarField=F>, PC: KZH<E>, F: PrimeField + Absorb> MultiPointOpening<E, PC, F> {
+    pub fn prove(
+        srs: &PC::SRS,
+        coms: &[PC::Commitment],
+        hints: &[PC::CommitmentHint],
+        polys: &[MultilinearPolynomial<F>],
+        points: &[Vec<F>],
+        transcript: &mut Transcript<F>,
+    ) -> Self {
+        assert_eq!(coms.len(), polys.len(), "number of commitments and polynomials must match");
+        assert_eq!(polys.len(), points.len(), "number of polynomials and points must match");
+        assert!(!points.is_empty(), "there should be at least one point");
+
+        let num_variables = points[0].len();
+        assert!(points.iter().all(|point| point.len() == num_variables), "all points must have the same length");
+        assert!(polys.iter().all(|poly| poly.num_variables <= num_variables), "polynomial has too many variables");
+
+        let mut polys_B: Vec<_> = polys.iter()
+            .map(|poly| poly.extend_number_of_variables(num_variables))
+            .collect();
+
+        let outputs: Vec<F> = polys_B.iter()
+            .zip(points.iter())
+            .map(|(poly, point)| poly.evaluate(point.as_slice()))
+            .collect();
+
+        let coeffs = get_batching_challenges::<E, _>(transcript, points.concat().as_slice(), outputs.as_slice(), coms);
+        let claim: F = coeffs.iter().zip(outputs.iter()).map(|(c, y)| *c * y).sum();
+
+        let mut polys_A: Vec<_> = points.iter()
+            .map(|point| MultilinearPolynomial::new(EqPolynomial::new(point.clone()).evals()))
+            .collect();
+
+        let (sumcheck_proof, rho, (_, evaluations)) = SumcheckInstanceProof::prove_quad_batched::<E>(
+            &claim,
+            num_variables,
+            &mut polys_A,
+            &mut polys_B,
+            coeffs.as_slice(),
+            transcript,
+        );
+
+        let opening = PC::batch_open(srs, rho.as_slice(), coms, hints, polys, transcript);
+
+        MultiPointOpening {
+            sumcheck_proof,
+            evaluations,
+            opening,
+        }
+    }
+
+    pub fn verify(
+        &self,
+        srs: &PC::SRS,
+        coms: &[PC::Commitment],
+        points: &[Vec<F>],
+        outputs: &[F],
+        transcript: &mut Transcript<F>,
+    ) -> Result<(), PCSError> {
+        if coms.len() != points.len() || coms.len() != outputs.len() || coms.len() != self.evaluations.len() || points.is_empty() {
+            return Err(PCSError::SumcheckFailed);
+        }
+
+        let num_variables = points[0].len();
+        if points.iter().any(|point| point.len() != num_variables) {
+            return Err(PCSError::SumcheckFailed);
+        }
+
+        // the sumcheck verifier asserts on the shape of the proof, so it's checked here first: one
+        // round per variable, each with a polynomial of degree 2
+        let rounds = &self.sumcheck_proof.compressed_polys;
+        if rounds.len() != num_variables || rounds.iter().any(|poly| poly.coeffs_except_linear_term.len() != 2) {
+            return Err(PCSError::SumcheckFailed);
+        }
+
+        let coeffs = get_batching_challenges::<E, _>(transcript, points.concat().as_slice(), outputs, coms);
+        let claim: F = coeffs.iter().zip(outputs.iter()).map(|(c, y)| *c * y).sum();
+
+        let (e, rho) = self.sumcheck_proof
+            .verify::<E>(claim, num_variables, 2, transcript)
+            .map_err(|_| PCSError::SumcheckFailed)?;
+
+        // the final sumcheck claim must be consistent with the evaluations at rho
+        let expected: F = coeffs.iter()
+            .zip(points.iter().zip(self.evaluations.iter()))
+            .map(|(c, (point, y))| *c * EqPolynomial::new(point.clone()).evaluate(rho.as_slice()) * y)
+            .sum();
+        if e != expected {
+            return Err(PCSError::SumcheckFailed);
+        }
+
+        PC::verify_batch_open(srs, rho.as_slice(), self.evaluations.as_slice(), coms, &self.opening, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::errors::PCSError;
+    use crate::kzh::kzh2::KZH2;
+    use crate::kzh::kzh3::KZH3;
+    use crate::kzh::multi_point::MultiPointOpening;
+    use crate::kzh::KZH;
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use crate::transcript::transcript::Transcript;
+
+    fn test_multi_point_opening_helper<PC: KZH<E>>() {
+        let num_vars = 8;
+        let srs = PC::setup(num_vars, &mut thread_rng());
+
+        let polys: Vec<MultilinearPolynomial<F>> = (0..3)
+            .map(|_| MultilinearPolynomial::rand(num_vars, &mut thread_rng()))
+            .collect();
+        let (coms, hints): (Vec<_>, Vec<_>) = polys.iter().map(|poly| PC::commit(&srs, poly)).unzip();
+
+        let points: Vec<Vec<F>> = (0..3)
+            .map(|_| (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect())
+            .collect();
+        let outputs: Vec<_> = polys.iter()
+            .zip(points.iter())
+            .map(|(poly, point)| poly.evaluate(point))
+            .collect();
+
+        let mut prover_transcript = Transcript::new(b"multi point");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let mut proof = MultiPointOpening::<E, PC, F>::prove(&srs, coms.as_slice(), hints.as_slice(), polys.as_slice(), points.as_slice(), &mut prover_transcript);
+        proof.verify(&srs, coms.as_slice(), points.as_slice(), outputs.as_slice(), &mut verifier_transcript).unwrap();
+
+        // a wrong claimed evaluation must be rejected
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[2] += F::from(1u64);
+        assert!(proof.verify(&srs, coms.as_slice(), points.as_slice(), wrong_outputs.as_slice(), &mut Transcript::new(b"multi point")).is_err());
+
+        // as well as a sumcheck proof with a missing round
+        let last_round = proof.sumcheck_proof.compressed_polys.pop().unwrap();
+        assert_eq!(
+            proof.verify(&srs, coms.as_slice(), points.as_slice(), outputs.as_slice(), &mut Transcript::new(b"multi point")),
+            Err(PCSError::SumcheckFailed)
+        );
+        proof.sumcheck_proof.compressed_polys.push(last_round);
+
+        // or with a round polynomial of the wrong degree
+        proof.sumcheck_proof.compressed_polys[0].coeffs_except_linear_term.push(F::from(1u64));
+        assert_eq!(
+            proof.verify(&srs, coms.as_slice(), points.as_slice(), outputs.as_slice(), &mut Transcript::new(b"multi point")),
+            Err(PCSError::SumcheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_multi_point_opening_kzh2() {
+        test_multi_point_opening_helper::<KZH2<E>>();
+    }
+
+    #[test]
+    fn test_multi_point_opening_kzh3() {
+        test_multi_point_opening_helper::<KZH3<E>>();
+    }
+}
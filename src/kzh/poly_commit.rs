@@ -0,0 +1,475 @@
+use std::marker::PhantomData;
+
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ec::pairing::Pairing;
+use ark_poly::DenseMultilinearExtension;
+use ark_poly_commit::{
+    Error, LabeledCommitment, LabeledPolynomial, PCCommitment, PCCommitmentState, PCCommitterKey,
+    PCUniversalParams, PCVerifierKey, PolynomialCommitment,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use derivative::Derivative;
+
+use crate::kzh::errors::PolyCommitError;
+use crate::kzh::kzh2::KZH2;
+use crate::kzh::kzh3::KZH3;
+use crate::kzh::kzh4::KZH4;
+use crate::kzh::{Homomorphic, KZH};
+use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+use crate::utils::compute_powers;
+
+/// Adapter implementing ark-poly-commit's `PolynomialCommitment` for multilinear polynomials on top of
+/// a KZH scheme, so that KZH can be plugged into arkworks-based protocols.
+///
+/// A `DenseMultilinearExtension` orders its evaluations with the first variable as the least
+/// significant bit while `MultilinearPolynomial` uses the most significant bit, so the evaluations are
+/// kept as they are and the points are reversed. The polynomials opened at the same point are combined
+/// with the powers of a challenge squeezed from the sponge and opened with a single KZH opening.
+pub struct KZHPolyCommit<E: Pairing, PC: KZH<E>>(PhantomData<(E, PC)>)
+where
+    <E as Pairing>::ScalarField: Absorb;
+
+pub type KZH2PolyCommit<E> = KZHPolyCommit<E, KZH2<E>>;
+
+pub type KZH3PolyCommit<E> = KZHPolyCommit<E, KZH3<E>>;
+
+pub type KZH4PolyCommit<E> = KZHPolyCommit<E, KZH4<E>>;
+
+/// The KZH srs, it serves as the universal parameters, the committer key and the verifier key
+#[derive(CanonicalSerialize, CanonicalDeserialize, Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct KZHPolyCommitParams<E: Pairing, PC: KZH<E>>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    #[derivative(Debug = "ignore")]
+    pub srs: PC::SRS,
+    /// number of variables supported by the srs
+    pub num_variables: usize,
+}
+
+/// A KZH commitment, the empty commitment is only there to provide the `Default` arkworks requires
+#[derive(CanonicalSerialize, CanonicalDeserialize, Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""), PartialEq(bound = ""), Eq(bound = ""))]
+pub struct KZHPolyCommitCommitment<E: Pairing, PC: KZH<E>>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    pub commitment: Option<PC::Commitment>,
+}
+
+/// The commitment hint, kept by the prover to open the commitment
+#[derive(CanonicalSerialize, CanonicalDeserialize, Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct KZHPolyCommitState<E: Pairing, PC: KZH<E>>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    pub hint: Option<PC::CommitmentHint>,
+}
+
+// multilinear polynomials have degree at most one in each variable
+impl<E: Pairing, PC: KZH<E>> PCUniversalParams for KZHPolyCommitParams<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    fn max_degree(&self) -> usize {
+        1
+    }
+}
+
+impl<E: Pairing, PC: KZH<E>> PCCommitterKey for KZHPolyCommitParams<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    fn max_degree(&self) -> usize {
+        1
+    }
+
+    fn supported_degree(&self) -> usize {
+        1
+    }
+}
+
+impl<E: Pairing, PC: KZH<E>> PCVerifierKey for KZHPolyCommitParams<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    fn max_degree(&self) -> usize {
+        1
+    }
+
+    fn supported_degree(&self) -> usize {
+        1
+    }
+}
+
+impl<E: Pairing, PC: KZH<E>> PCCommitment for KZHPolyCommitCommitment<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    fn empty() -> Self {
+        KZHPolyCommitCommitment { commitment: None }
+    }
+
+    fn has_degree_bound(&self) -> bool {
+        false
+    }
+}
+
+impl<E: Pairing, PC: KZH<E>> PCCommitmentState for KZHPolyCommitState<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    // the commitments are not hiding, there is no randomness
+    type Randomness = ();
+
+    fn empty() -> Self {
+        KZHPolyCommitState { hint: None }
+    }
+
+    fn rand<R: RngCore>(_num_queries: usize, _has_degree_bound: bool, _num_vars: Option<usize>, _rng: &mut R) -> Self::Randomness {}
+}
+
+impl<E: Pairing, PC: KZH<E>> KZHPolyCommit<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    /// Checks that the labeled polynomial can be committed with the srs and converts it to a
+    /// `MultilinearPolynomial`, its evaluations are in the same order once the points are reversed
+    fn to_multilinear(
+        params: &KZHPolyCommitParams<E, PC>,
+        poly: &LabeledPolynomial<E::ScalarField, DenseMultilinearExtension<E::ScalarField>>,
+    ) -> Result<MultilinearPolynomial<E::ScalarField>, PolyCommitError> {
+        if let Some(degree_bound) = poly.degree_bound() {
+            return Err(Error::UnsupportedDegreeBound(degree_bound).into());
+        }
+        if poly.hiding_bound().is_some() {
+            return Err(PolyCommitError::HidingUnsupported);
+        }
+        if poly.polynomial().num_vars > params.num_variables {
+            return Err(PolyCommitError::TooManyVariables {
+                num_variables: poly.polynomial().num_vars,
+                max_num_variables: params.num_variables,
+            });
+        }
+
+        Ok(MultilinearPolynomial::new(poly.polynomial().evaluations.clone()))
+    }
+
+    /// Reverses the point to the variable order of `MultilinearPolynomial`, the KZH open and verify
+    /// pad it with zeros at the beginning just like the polynomial is padded in commit
+    fn to_input(
+        params: &KZHPolyCommitParams<E, PC>,
+        point: &[E::ScalarField],
+    ) -> Result<Vec<E::ScalarField>, PolyCommitError> {
+        if point.len() > params.num_variables {
+            return Err(PolyCommitError::TooManyVariables {
+                num_variables: point.len(),
+                max_num_variables: params.num_variables,
+            });
+        }
+
+        Ok(point.iter().rev().cloned().collect())
+    }
+
+    /// Returns [1, r, ..., r^(n-1)] for a challenge r squeezed from the sponge
+    fn get_combination_coefficients(sponge: &mut impl CryptographicSponge, n: usize) -> Vec<E::ScalarField> {
+        let r: E::ScalarField = sponge.squeeze_field_elements(1)[0];
+        compute_powers(&r, n)
+    }
+}
+
+impl<E: Pairing, PC: KZH<E>> PolynomialCommitment<E::ScalarField, DenseMultilinearExtension<E::ScalarField>> for KZHPolyCommit<E, PC>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    type UniversalParams = KZHPolyCommitParams<E, PC>;
+    type CommitterKey = KZHPolyCommitParams<E, PC>;
+    type VerifierKey = KZHPolyCommitParams<E, PC>;
+    type Commitment = KZHPolyCommitCommitment<E, PC>;
+    type CommitmentState = KZHPolyCommitState<E, PC>;
+    type Proof = PC::Opening;
+    type BatchProof = Vec<Self::Proof>;
+    type Error = PolyCommitError;
+
+    /// The srs is generated for `num_vars` variables, `max_degree` is ignored since multilinear
+    /// polynomials have degree one in each variable
+    fn setup<R: RngCore>(
+        _max_degree: usize,
+        num_vars: Option<usize>,
+        rng: &mut R,
+    ) -> Result<Self::UniversalParams, Self::Error> {
+        let num_variables = num_vars.ok_or(PolyCommitError::MissingNumVars)?;
+
+        Ok(KZHPolyCommitParams {
+            srs: PC::setup(num_variables, rng),
+            num_variables,
+        })
+    }
+
+    fn trim(
+        pp: &Self::UniversalParams,
+        _supported_degree: usize,
+        supported_hiding_bound: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
+        if supported_hiding_bound > 0 {
+            return Err(PolyCommitError::HidingUnsupported);
+        }
+        if let Some(&degree_bound) = enforced_degree_bounds.and_then(|bounds| bounds.first()) {
+            return Err(Error::UnsupportedDegreeBound(degree_bound).into());
+        }
+
+        Ok((pp.clone(), pp.clone()))
+    }
+
+    fn commit<'a>(
+        ck: &Self::CommitterKey,
+        polynomials: impl IntoIterator<Item=&'a LabeledPolynomial<E::ScalarField, DenseMultilinearExtension<E::ScalarField>>>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Vec<LabeledCommitment<Self::Commitment>>, Vec<Self::CommitmentState>), Self::Error>
+    where
+        DenseMultilinearExtension<E::ScalarField>: 'a,
+    {
+        let mut commitments = Vec::new();
+        let mut states = Vec::new();
+
+        for poly in polynomials {
+            let (com, hint) = PC::commit(&ck.srs, &Self::to_multilinear(ck, poly)?);
+            commitments.push(LabeledCommitment::new(
+                poly.label().clone(),
+                KZHPolyCommitCommitment { commitment: Some(com) },
+                None,
+            ));
+            states.push(KZHPolyCommitState { hint: Some(hint) });
+        }
+
+        Ok((commitments, states))
+    }
+
+    fn open<'a>(
+        ck: &Self::CommitterKey,
+        labeled_polynomials: impl IntoIterator<Item=&'a LabeledPolynomial<E::ScalarField, DenseMultilinearExtension<E::ScalarField>>>,
+        _commitments: impl IntoIterator<Item=&'a LabeledCommitment<Self::Commitment>>,
+        point: &'a Vec<E::ScalarField>,
+        sponge: &mut impl CryptographicSponge,
+        states: impl IntoIterator<Item=&'a Self::CommitmentState>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        Self::Commitment: 'a,
+        Self::CommitmentState: 'a,
+        DenseMultilinearExtension<E::ScalarField>: 'a,
+    {
+        let mut polys = Vec::new();
+        for poly in labeled_polynomials {
+            if poly.polynomial().num_vars != point.len() {
+                return Err(PolyCommitError::PointLengthMismatch {
+                    point_length: point.len(),
+                    num_variables: poly.polynomial().num_vars,
+                });
+            }
+            polys.push(Self::to_multilinear(ck, poly)?);
+        }
+        let hints = states.into_iter()
+            .map(|state| state.hint.as_ref().ok_or(PolyCommitError::EmptyCommitment))
+            .collect::<Result<Vec<_>, _>>()?;
+        if polys.is_empty() || polys.len() != hints.len() {
+            return Err(PolyCommitError::InputLengthMismatch);
+        }
+
+        let coeffs = Self::get_combination_coefficients(sponge, polys.len());
+        // MultilinearPolynomial has an inherent linear_combination of two polynomials, hence the UFCS
+        let poly = <MultilinearPolynomial<E::ScalarField> as Homomorphic<E::ScalarField>>::linear_combination(
+            coeffs.iter().cloned().zip(polys.iter()).collect::<Vec<_>>().as_slice(),
+        );
+        let hint = PC::CommitmentHint::linear_combination(
+            coeffs.iter().cloned().zip(hints.into_iter()).collect::<Vec<_>>().as_slice(),
+        );
+
+        Ok(PC::open(&ck.srs, Self::to_input(ck, point)?.as_slice(), &hint, &poly))
+    }
+
+    fn check<'a>(
+        vk: &Self::VerifierKey,
+        commitments: impl IntoIterator<Item=&'a LabeledCommitment<Self::Commitment>>,
+        point: &'a Vec<E::ScalarField>,
+        values: impl IntoIterator<Item=E::ScalarField>,
+        proof: &Self::Proof,
+        sponge: &mut impl CryptographicSponge,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        let coms = commitments.into_iter()
+            .map(|com| com.commitment().commitment.as_ref().ok_or(PolyCommitError::EmptyCommitment))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values: Vec<E::ScalarField> = values.into_iter().collect();
+        if coms.is_empty() || coms.len() != values.len() {
+            return Err(PolyCommitError::InputLengthMismatch);
+        }
+
+        let coeffs = Self::get_combination_coefficients(sponge, coms.len());
+        let com = PC::Commitment::linear_combination(
+            coeffs.iter().cloned().zip(coms.into_iter()).collect::<Vec<_>>().as_slice(),
+        );
+        let value: E::ScalarField = coeffs.iter().zip(values.iter()).map(|(c, v)| *c * v).sum();
+
+        Ok(PC::verify(&vk.srs, Self::to_input(vk, point)?.as_slice(), &value, &com, proof).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_crypto_primitives::sponge::CryptographicSponge;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+    use ark_poly_commit::{Evaluations, LabeledPolynomial, PolynomialCommitment, QuerySet};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::hash::poseidon::get_poseidon_config;
+    use crate::kzh::kzh2::KZH2;
+    use crate::kzh::kzh3::KZH3;
+    use crate::kzh::kzh4::KZH4;
+    use crate::kzh::poly_commit::KZHPolyCommit;
+    use crate::kzh::KZH;
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+
+    type Sponge = PoseidonSponge<F>;
+
+    fn sponge() -> Sponge {
+        PoseidonSponge::new(&get_poseidon_config::<F>())
+    }
+
+    fn rand_point(num_vars: usize) -> Vec<F> {
+        (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect()
+    }
+
+    /// The variable order of DenseMultilinearExtension is the reverse of MultilinearPolynomial
+    #[test]
+    fn test_variable_order() {
+        let poly = DenseMultilinearExtension::<F>::rand(5, &mut thread_rng());
+        let point = rand_point(5);
+        let reversed: Vec<F> = point.iter().rev().cloned().collect();
+
+        assert_eq!(
+            poly.evaluate(&point),
+            MultilinearPolynomial::new(poly.evaluations.clone()).evaluate(&reversed),
+        );
+    }
+
+    /// Commits to labeled polynomials of different sizes and opens all of them at a single point, in
+    /// the style of the single polynomial and multiple queries tests of ark-poly-commit
+    fn test_single_point_helper<PC: KZH<E>>() {
+        let pp = KZHPolyCommit::<E, PC>::setup(1, Some(8), &mut thread_rng()).unwrap();
+        let (ck, vk) = KZHPolyCommit::<E, PC>::trim(&pp, 1, 0, None).unwrap();
+
+        for num_vars in [8, 6] {
+            let polys: Vec<_> = (0..3)
+                .map(|i| LabeledPolynomial::new(
+                    format!("poly_{i}"),
+                    DenseMultilinearExtension::rand(num_vars, &mut thread_rng()),
+                    None,
+                    None,
+                ))
+                .collect();
+            let (coms, states) = KZHPolyCommit::<E, PC>::commit(&ck, &polys, None).unwrap();
+
+            let point = rand_point(num_vars);
+            let values: Vec<F> = polys.iter().map(|poly| poly.evaluate(&point)).collect();
+
+            let proof = KZHPolyCommit::<E, PC>::open(&ck, &polys, &coms, &point, &mut sponge(), &states, None).unwrap();
+            assert!(KZHPolyCommit::<E, PC>::check(&vk, &coms, &point, values.clone(), &proof, &mut sponge(), None).unwrap());
+
+            // a wrong value must be rejected
+            let mut wrong_values = values.clone();
+            wrong_values[1] += F::from(1u64);
+            assert!(!KZHPolyCommit::<E, PC>::check(&vk, &coms, &point, wrong_values, &proof, &mut sponge(), None).unwrap());
+        }
+    }
+
+    /// Opens several labeled polynomials at several points through a query set, in the style of the
+    /// batch queries tests of ark-poly-commit
+    fn test_batch_queries_helper<PC: KZH<E>>() {
+        let num_vars = 8;
+        let pp = KZHPolyCommit::<E, PC>::setup(1, Some(num_vars), &mut thread_rng()).unwrap();
+        let (ck, vk) = KZHPolyCommit::<E, PC>::trim(&pp, 1, 0, None).unwrap();
+
+        let polys: Vec<_> = (0..4)
+            .map(|i| LabeledPolynomial::new(
+                format!("poly_{i}"),
+                DenseMultilinearExtension::rand(num_vars, &mut thread_rng()),
+                None,
+                None,
+            ))
+            .collect();
+        let (coms, states) = KZHPolyCommit::<E, PC>::commit(&ck, &polys, None).unwrap();
+
+        let mut query_set = QuerySet::new();
+        let mut values = Evaluations::new();
+        for j in 0..3 {
+            let point = rand_point(num_vars);
+            // each point is queried on a different subset of the polynomials
+            for poly in polys.iter().skip(j) {
+                query_set.insert((poly.label().clone(), (format!("point_{j}"), point.clone())));
+                values.insert((poly.label().clone(), point.clone()), poly.evaluate(&point));
+            }
+        }
+
+        let proof = KZHPolyCommit::<E, PC>::batch_open(&ck, &polys, &coms, &query_set, &mut sponge(), &states, None).unwrap();
+        assert!(KZHPolyCommit::<E, PC>::batch_check(&vk, &coms, &query_set, &values, &proof, &mut sponge(), &mut thread_rng()).unwrap());
+
+        // a wrong value must be rejected
+        let (key, value) = values.iter().next().map(|(key, value)| (key.clone(), *value)).unwrap();
+        values.insert(key, value + F::from(1u64));
+        assert!(!KZHPolyCommit::<E, PC>::batch_check(&vk, &coms, &query_set, &values, &proof, &mut sponge(), &mut thread_rng()).unwrap());
+    }
+
+    #[test]
+    fn test_single_point_kzh2() {
+        test_single_point_helper::<KZH2<E>>();
+    }
+
+    #[test]
+    fn test_single_point_kzh3() {
+        test_single_point_helper::<KZH3<E>>();
+    }
+
+    #[test]
+    fn test_single_point_kzh4() {
+        test_single_point_helper::<KZH4<E>>();
+    }
+
+    #[test]
+    fn test_batch_queries_kzh2() {
+        test_batch_queries_helper::<KZH2<E>>();
+    }
+
+    #[test]
+    fn test_batch_queries_kzh3() {
+        test_batch_queries_helper::<KZH3<E>>();
+    }
+
+    #[test]
+    fn test_batch_queries_kzh4() {
+        test_batch_queries_helper::<KZH4<E>>();
+    }
+
+    /// Degree bounds and hiding have no meaning for this adapter and are rejected
+    #[test]
+    fn test_unsupported_options() {
+        let pp = KZHPolyCommit::<E, KZH2<E>>::setup(1, Some(4), &mut thread_rng()).unwrap();
+        assert!(KZHPolyCommit::<E, KZH2<E>>::trim(&pp, 1, 1, None).is_err());
+        assert!(KZHPolyCommit::<E, KZH2<E>>::trim(&pp, 1, 0, Some(&[1])).is_err());
+        assert!(KZHPolyCommit::<E, KZH2<E>>::setup(1, None, &mut thread_rng()).is_err());
+
+        let (ck, _) = KZHPolyCommit::<E, KZH2<E>>::trim(&pp, 1, 0, None).unwrap();
+        let too_large = LabeledPolynomial::new("poly".to_string(), DenseMultilinearExtension::rand(5, &mut thread_rng()), None, None);
+        assert!(KZHPolyCommit::<E, KZH2<E>>::commit(&ck, &[too_large], None).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::kzh::errors::PCSError;
+use crate::kzh::{add_vectors, scale_vector, split_input_by_degrees, Homomorphic, KZH};
+use crate::nexus_spartan::commitment_traits::ToAffine;
+use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
+use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+use crate::transcript::transcript::{AppendToTranscript, Transcript};
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{AdditiveGroup, One, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use derivative::Derivative;
+use rand::Rng;
+use rayon::prelude::*;
+use std::marker::PhantomData;
+use std::ops::Mul;
+
+/// Multilinear KZG of Papamanthou, Shi and Tamassia (PST13), the baseline the KZH schemes are compared
+/// to: the commitment is g^f(tau), an opening at z has one quotient commitment per variable and the
+/// verifier computes n + 1 pairings
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct PST13<E: Pairing> {
+    phantom: PhantomData<E>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct PST13SRS<E: Pairing> {
+    /// number of variables n of the supported polynomials
+    pub num_variables: usize,
+    /// H[k] = g * eq(tau_{k+1}, ..., tau_n, b) for all b in {0, 1}^(n - k), i.e. the Lagrange basis
+    /// over the last n - k variables, H[n] = [g]
+    pub H: Vec<Vec<E::G1Affine>>,
+    pub g: E::G1Affine,
+    pub v: E::G2Affine,
+    /// V[k] = v * tau_k
+    pub V: Vec<E::G2Affine>,
+}
+
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct PST13Commitment<E: Pairing> {
+    /// the commitment C = g^f(tau) to the polynomial
+    pub C: E::G1Affine,
+}
+
+/// PST13 needs no prover-only data to open, the hint is empty
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+    Derivative
+)]
+pub struct PST13CommitmentHint<E: Pairing> {
+    phantom: PhantomData<E>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct PST13Opening<E: Pairing> {
+    /// commitments to the quotients q_1, ..., q_n with f(X) - f(z) = sum_k (X_k - z_k) * q_k(X_{k+1}, ..., X_n)
+    pub pi: Vec<E::G1>,
+}
+
+impl<E: Pairing> KZH<E> for PST13<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    type Degree = usize;
+    type SRS = PST13SRS<E>;
+    type Commitment = PST13Commitment<E>;
+    type CommitmentHint = PST13CommitmentHint<E>;
+    type Opening = PST13Opening<E>;
+
+    fn split_input<T: Clone>(srs: &Self::SRS, input: &[T], default: T) -> Vec<Vec<T>> {
+        // a single dimension, the input is only padded with zeros at the beginning
+        split_input_by_degrees(&[1 << srs.num_variables], input, default)
+    }
+
+    fn get_degree_from_maximum_supported_degree(n: usize) -> usize {
+        n
+    }
+
+    fn setup_with_degrees<R: Rng>(degrees: Self::Degree, rng: &mut R) -> Self::SRS {
+        let num_variables = degrees;
+
+        let (g, v) = (E::G1Affine::rand(rng), E::G2Affine::rand(rng));
+
+        let tau: Vec<E::ScalarField> = (0..num_variables).map(|_| E::ScalarField::rand(rng)).collect();
+
+        let H: Vec<Vec<E::G1Affine>> = (0..=num_variables)
+            .map(|k| {
+                EqPolynomial::new(tau[k..].to_vec())
+                    .evals()
+                    .into_par_iter()
+                    .map(|e| g.mul(e).into())
+                    .collect()
+            })
+            .collect();
+
+        let V: Vec<_> = (0..num_variables)
+            .map(|k| v.mul(tau[k]).into())
+            .collect();
+
+        PST13SRS {
+            num_variables,
+            H,
+            g,
+            v,
+            V,
+        }
+    }
+
+    fn commit(srs: &Self::SRS, poly: &MultilinearPolynomial<E::ScalarField>) -> (Self::Commitment, Self::CommitmentHint) {
+        assert!(poly.num_variables <= srs.num_variables, "polynomial has more variables than the srs supports");
+        let poly = poly.extend_number_of_variables(srs.num_variables);
+
+        let com = PST13Commitment {
+            C: E::G1::msm_unchecked(srs.H[0].as_slice(), poly.evaluation_over_boolean_hypercube.as_slice()).into(),
+        };
+
+        (com, PST13CommitmentHint { phantom: PhantomData })
+    }
+
+    fn open(srs: &Self::SRS, input: &[E::ScalarField], _hint: &Self::CommitmentHint, poly: &MultilinearPolynomial<E::ScalarField>) -> Self::Opening {
+        let poly = poly.extend_number_of_variables(srs.num_variables);
+        let point = Self::split_input(&srs, input, E::ScalarField::ZERO).concat();
+
+        // the first variable is the most significant bit, so f(0, ...) and f(1, ...) are the two halves
+        // of the evaluations, f(X_k, ...) - f(z_k, ...) = (X_k - z_k) * q_k with q_k = f(1, ...) - f(0, ...)
+        let mut f = poly.evaluation_over_boolean_hypercube;
+        let mut pi = Vec::with_capacity(srs.num_variables);
+        for k in 0..srs.num_variables {
+            let (f_0, f_1) = f.split_at(f.len() / 2);
+            let q: Vec<_> = f_1.par_iter().zip(f_0.par_iter()).map(|(b, a)| *b - a).collect();
+
+            pi.push(E::G1::msm_unchecked(srs.H[k + 1].as_slice(), q.as_slice()));
+
+            f = f_0.par_iter().zip(q.par_iter()).map(|(a, q)| *a + point[k] * q).collect();
+        }
+
+        PST13Opening { pi }
+    }
+
+    fn verify(srs: &Self::SRS, input: &[E::ScalarField], output: &E::ScalarField, com: &Self::Commitment, open: &Self::Opening) -> Result<(), PCSError> {
+        if open.pi.len() != srs.num_variables || input.len() > srs.num_variables {
+            return Err(PCSError::PairingCheckFailed);
+        }
+        let point = Self::split_input(&srs, input, E::ScalarField::ZERO).concat();
+
+        // e(C - g * output, v) = prod_k e(pi_k, v * (tau_k - z_k))
+        let g1_elems = E::G1::normalize_batch(
+            std::iter::once(com.C.into_group() - srs.g.mul(output))
+                .chain(open.pi.iter().map(|pi| -*pi))
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        let g2_elems = E::G2::normalize_batch(
+            std::iter::once(srs.v.into_group())
+                .chain(srs.V.iter().zip(point.iter()).map(|(V, z)| V.into_group() - srs.v.mul(z)))
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+
+        if !E::multi_pairing(g1_elems, g2_elems).0.is_one() {
+            return Err(PCSError::PairingCheckFailed);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Pairing, F: PrimeField + Absorb> AppendToTranscript<F> for PST13Commitment<E>
+where
+    E: Pairing<ScalarField=F>,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    fn append_to_transcript(&self, label: &'static [u8], transcript: &mut Transcript<F>) {
+        Transcript::append_point::<E>(transcript, label, &self.C);
+    }
+}
+
+impl<E: Pairing> ToAffine<E> for PST13Commitment<E> {
+    fn to_affine(self) -> E::G1Affine {
+        self.C
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for PST13Commitment<E> {
+    fn add(&self, other: &Self) -> Self {
+        PST13Commitment { C: (self.C + other.C).into_affine() }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        PST13Commitment { C: self.C.mul(r).into_affine() }
+    }
+
+    fn linear_combination(terms: &[(E::ScalarField, &Self)]) -> Self {
+        assert!(!terms.is_empty(), "there should be at least one term");
+
+        // accumulate in projective form to normalize only once
+        let C = terms.iter().fold(E::G1::ZERO, |acc, (coeff, com)| acc + com.C.mul(coeff));
+        PST13Commitment { C: C.into_affine() }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for PST13CommitmentHint<E> {
+    fn add(&self, _other: &Self) -> Self {
+        PST13CommitmentHint { phantom: PhantomData }
+    }
+
+    fn scale(&self, _r: &E::ScalarField) -> Self {
+        PST13CommitmentHint { phantom: PhantomData }
+    }
+}
+
+impl<E: Pairing> Homomorphic<E::ScalarField> for PST13Opening<E> {
+    fn add(&self, other: &Self) -> Self {
+        PST13Opening {
+            pi: add_vectors(&self.pi, &other.pi),
+        }
+    }
+
+    fn scale(&self, r: &E::ScalarField) -> Self {
+        PST13Opening {
+            pi: scale_vector(&self.pi, r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::errors::PCSError;
+    use crate::kzh::pst13::{PST13, PST13SRS};
+    use crate::kzh::KZH;
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use crate::transcript::transcript::Transcript;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn pcs_test() {
+        let srs: PST13SRS<E> = PST13::setup(8, &mut thread_rng());
+
+        for num_vars in [8, 5] {
+            let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+            let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+            let eval = polynomial.evaluate(input.as_slice());
+
+            let (com, hint) = PST13::commit(&srs, &polynomial);
+            let open = PST13::open(&srs, input.as_slice(), &hint, &polynomial);
+            assert_eq!(open.pi.len(), 8);
+
+            PST13::verify(&srs, input.as_slice(), &eval, &com, &open).unwrap();
+            assert_eq!(
+                PST13::verify(&srs, input.as_slice(), &(eval + F::from(1u64)), &com, &open),
+                Err(PCSError::PairingCheckFailed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_open() {
+        let srs: PST13SRS<E> = PST13::setup(8, &mut thread_rng());
+
+        let polys: Vec<MultilinearPolynomial<F>> = (0..3)
+            .map(|_| MultilinearPolynomial::rand(8, &mut thread_rng()))
+            .collect();
+        let (coms, hints): (Vec<_>, Vec<_>) = polys.iter().map(|poly| PST13::commit(&srs, poly)).unzip();
+
+        let input: Vec<F> = (0..8).map(|_| F::rand(&mut thread_rng())).collect();
+        let outputs: Vec<F> = polys.iter().map(|poly| poly.evaluate(input.as_slice())).collect();
+
+        let mut prover_transcript = Transcript::new(b"batch open");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let open = PST13::batch_open(&srs, input.as_slice(), coms.as_slice(), hints.as_slice(), polys.as_slice(), &mut prover_transcript);
+        PST13::verify_batch_open(&srs, input.as_slice(), outputs.as_slice(), coms.as_slice(), &open, &mut verifier_transcript).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+//! Import of a KZH SRS from the transcript of a setup ceremony.
+//!
+//! `KZH2::setup` and its KZH3/KZH4 equivalents sample the trapdoors from a local RNG, so whoever
+//! runs them can forge openings. A production SRS is instead read from the transcript of the
+//! multi-party ceremony of [`crate::kzh::ceremony`], whose format is documented there, and it is
+//! only accepted once every contribution of the transcript verifies.
+//!
+//! Universal powers-of-tau ceremonies are not supported. Deriving the SRS from one, by choosing
+//! every trapdoor as a power of a single tau, is not sound: the rows of H_xy are then multiples of
+//! each other and a prover can shift its row commitments between rows without changing the pairing
+//! check, which lets it open to a wrong value.
+
+use crate::kzh::ceremony::{Ceremony, UpdatableSRS};
+use crate::kzh::errors::SRSError;
+use ark_ec::pairing::Pairing;
+use ark_serialize::Read;
+use rand::Rng;
+
+/// Reads a ceremony transcript and returns its final SRS, the transcript must have at least one
+/// contribution and all of them must verify
+pub fn import_srs<E: Pairing, S: UpdatableSRS<E>, Rd: Read, R: Rng>(reader: Rd, rng: &mut R) -> Result<S, SRSError> {
+    Ok(Ceremony::<E, S>::load(reader, rng)?.srs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::errors::{CeremonyError, PCSError};
+    use crate::kzh::kzh2::{KZH2, KZH2SRS};
+    use crate::kzh::KZH;
+    use crate::math::Math;
+    use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{Field, One};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use std::ops::Mul;
+
+    #[test]
+    fn test_import_srs() {
+        let mut ceremony = Ceremony::<E, KZH2SRS<E>>::new(6);
+
+        // a transcript without contributions is rejected
+        let mut bytes = Vec::new();
+        ceremony.save(&mut bytes).unwrap();
+        assert!(matches!(
+            import_srs::<E, KZH2SRS<E>, _, _>(bytes.as_slice(), &mut thread_rng()),
+            Err(SRSError::InvalidCeremony(CeremonyError::NoContributions))
+        ));
+
+        for _ in 0..2 {
+            ceremony.contribute(&mut thread_rng());
+        }
+        let mut bytes = Vec::new();
+        ceremony.save(&mut bytes).unwrap();
+        let srs: KZH2SRS<E> = import_srs(bytes.as_slice(), &mut thread_rng()).unwrap();
+        assert_eq!(srs, ceremony.srs());
+
+        // so is a transcript with a contribution which does not verify
+        let mut tampered = ceremony.clone();
+        tampered.contributions[1].srs = ceremony.contributions[0].srs.clone();
+        let mut bytes = Vec::new();
+        tampered.save(&mut bytes).unwrap();
+        assert!(matches!(
+            import_srs::<E, KZH2SRS<E>, _, _>(bytes.as_slice(), &mut thread_rng()),
+            Err(SRSError::InvalidCeremony(CeremonyError::InvalidUpdate { contribution: 1 }))
+        ));
+
+        // as well as a truncated one
+        assert!(matches!(
+            import_srs::<E, KZH2SRS<E>, _, _>(&bytes[..bytes.len() - 1], &mut thread_rng()),
+            Err(SRSError::SerializationError(_))
+        ));
+    }
+
+    /// Opens the polynomial honestly and then forges the opening for another output, assuming row 1
+    /// of H_xy is a multiple of row 0 with V_x shifted accordingly: with c_0 = eq_1(x) and
+    /// c_1 = -eq_0(x), moving c_k * H_xy[1][0] into D_k and -c_k * H_y[0] into D_(k+1) keeps the
+    /// pairing check, and the MSM check moves by -(c_0 * eq_1(x) + c_1 * eq_2(x)) * H_y[0] which is
+    /// absorbed into f_star[0]. Returns the verification of the forged opening
+    fn forge_opening(srs: &KZH2SRS<E>, polynomial: &MultilinearPolynomial<F>, input: &[F]) -> Result<(), PCSError> {
+        let (com, hint) = KZH2::commit(srs, polynomial);
+        let mut open = KZH2::open(srs, input, &hint, polynomial);
+        let (x, y) = input.split_at(srs.degree_x.log_2());
+
+        let eq = EqPolynomial::new(x.to_vec()).evals();
+        let (c_0, c_1) = (eq[1], -eq[0]);
+        let (shifted, g) = (srs.H_xy[1][0], srs.H_y[0]);
+        let D_x = [
+            open.D_x[0].into_group() + shifted.mul(c_0),
+            open.D_x[1].into_group() - g.mul(c_0) + shifted.mul(c_1),
+            open.D_x[2].into_group() - g.mul(c_1),
+        ];
+        open.D_x[..3].copy_from_slice(E::G1::normalize_batch(&D_x).as_slice());
+        open.f_star.evaluation_over_boolean_hypercube[0] -= c_0 * eq[1] + c_1 * eq[2];
+
+        let forged_output = open.f_star.evaluate(y);
+        assert_ne!(forged_output, polynomial.evaluate(input));
+
+        KZH2::verify(srs, input, &forged_output, &com, &open)
+    }
+
+    #[test]
+    fn test_structured_srs_forgery() {
+        let num_vars = 6;
+        let polynomial: MultilinearPolynomial<F> = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+
+        // every trapdoor a power of a single tau: tau_i = tau^(degree_y * i), G_j = tau^j * G and alpha = 1
+        let initial = KZH2SRS::<E>::initial(KZH2SRS::<E>::default_degrees(num_vars).as_slice());
+        let tau = F::rand(&mut thread_rng());
+        let secrets: Vec<F> = (0..initial.degree_x).map(|i| tau.pow([(initial.degree_y * i) as u64]))
+            .chain([F::one()])
+            .chain((0..initial.degree_y).map(|j| tau.pow([j as u64])))
+            .chain([F::one()])
+            .collect();
+        let single_tau = initial.update(secrets.as_slice());
+        single_tau.check_well_formed(&mut thread_rng()).unwrap();
+        assert!(forge_opening(&single_tau, &polynomial, input.as_slice()).is_ok());
+
+        // the initial SRS has the same structure, so a ceremony needs at least one contribution
+        let mut ceremony = Ceremony::<E, KZH2SRS<E>>::new(num_vars);
+        assert_eq!(ceremony.verify(&mut thread_rng()), Err(CeremonyError::NoContributions));
+        assert!(forge_opening(&ceremony.srs(), &polynomial, input.as_slice()).is_ok());
+
+        ceremony.contribute(&mut thread_rng());
+        ceremony.verify(&mut thread_rng()).unwrap();
+        assert!(forge_opening(&ceremony.srs(), &polynomial, input.as_slice()).is_err());
+    }
+}
@@ -227,7 +227,7 @@ mod test {
             let num_cons = num_vars;
 
             // this generates a new instance/witness for spartan as well as PCS parameters
-            let (spartan_shape, spartan_instance, spartan_witness, spartan_key) = produce_synthetic_crr1cs::<E, KZH2<E>>(num_cons, num_vars, num_inputs);
+            let (spartan_shape, spartan_instance, spartan_witness, spartan_hint, spartan_key) = produce_synthetic_crr1cs::<E, KZH2<E>>(num_cons, num_vars, num_inputs);
 
             assert!(is_sat(&spartan_shape, &spartan_instance, &spartan_witness, &spartan_key.gens_r1cs_sat).unwrap());
 
@@ -240,6 +240,7 @@ mod test {
                 &spartan_shape,
                 &spartan_instance,
                 spartan_witness,
+                &spartan_hint,
                 &spartan_key.gens_r1cs_sat,
                 &mut prover_transcript,
             );
@@ -296,7 +297,7 @@ mod test {
                 &spartan_proof.eval_vars_at_ry,
                 &commitment_w,
                 &opening_proof,
-            );
+            ).unwrap();
 
             let (x, y) = {
                 let split_input = KZH2::split_input(&pcs_srs, &ry[1..], F::zero());
@@ -410,7 +411,7 @@ mod test {
         let min_num_vars = CRSNARKKey::<E, KZH2<E>>::get_min_num_vars(shape.get_num_cons(), shape.get_num_vars(), shape.get_num_inputs());
         let SRS: KZH2SRS<E> = KZH2::setup(min_num_vars + 1, &mut thread_rng());
 
-        let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+        let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
         let witness = CRR1CSWitness::<F>::convert(cs.clone());
 
         let mut new_prover_transcript = Transcript::new(b"example");
@@ -418,6 +419,7 @@ mod test {
             &shape,
             &instance,
             witness,
+            &hint,
             &SRS,
             &mut new_prover_transcript,
         );
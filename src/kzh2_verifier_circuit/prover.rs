@@ -383,8 +383,8 @@ where
     let commitment_pp = KZH2VerifierCircuitProver::<G1, G2, C2, E, F>::get_commitment_pp(&shape);
 
     // get two random accumulators
-    let current_accumulator = Accumulator2::rand(&srs, &mut thread_rng());
-    let running_accumulator = Accumulator2::rand(&srs, &mut thread_rng());
+    let current_accumulator = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+    let running_accumulator = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
 
 
     let prover: KZH2VerifierCircuitProver<G1, G2, C2, E, F> = KZH2VerifierCircuitProver::new(
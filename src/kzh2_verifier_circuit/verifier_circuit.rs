@@ -651,7 +651,7 @@ pub mod tests {
         // convert to the corresponding Spartan types
         let shape = CRR1CSShape::<ScalarField>::convert::<G1>(cs.clone());
         // Commitment to w(x) happens here
-        let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+        let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
 
         let witness = CRR1CSWitness::<ScalarField>::convert(cs.clone());
 
@@ -666,6 +666,7 @@ pub mod tests {
             &shape,
             &instance,
             witness,
+            &hint,
             &SRS,
             &mut prover_transcript,
         );
@@ -220,7 +220,7 @@ mod test {
 
 
             // this generates a new instance/witness for spartan as well as PCS parameters
-            let (spartan_shape, spartan_instance, spartan_witness, spartan_key) = produce_synthetic_crr1cs::<E, KZH3<E>>(num_cons, num_vars, num_inputs);
+            let (spartan_shape, spartan_instance, spartan_witness, spartan_hint, spartan_key) = produce_synthetic_crr1cs::<E, KZH3<E>>(num_cons, num_vars, num_inputs);
 
             assert!(is_sat(&spartan_shape, &spartan_instance, &spartan_witness, &spartan_key.gens_r1cs_sat).unwrap());
 
@@ -233,6 +233,7 @@ mod test {
                 &spartan_shape,
                 &spartan_instance,
                 spartan_witness,
+                &spartan_hint,
                 &spartan_key.gens_r1cs_sat,
                 &mut prover_transcript,
             );
@@ -291,7 +292,7 @@ mod test {
                 &spartan_proof.eval_vars_at_ry,
                 &commitment_w,
                 &opening_proof,
-            );
+            ).unwrap();
 
             // Get accumulator from the opening proof
             let acc_instance = Accumulator::proof_to_accumulator_instance(
@@ -304,7 +305,6 @@ mod test {
 
             let acc_witness = Accumulator::proof_to_accumulator_witness(
                 &acc_srs,
-                commitment_w,
                 opening_proof,
                 &input,
             );
@@ -395,7 +395,7 @@ mod test {
         let min_num_vars = CRSNARKKey::<E, KZH3<E>>::get_min_num_vars(shape.get_num_cons(), shape.get_num_vars(), shape.get_num_inputs());
         let SRS: KZH3SRS<E> = KZH3::setup(min_num_vars + 1, &mut thread_rng());
 
-        let instance: CRR1CSInstance<E, KZH3<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+        let (instance, hint): (CRR1CSInstance<E, KZH3<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
         let witness = CRR1CSWitness::<F>::convert(cs.clone());
 
         let mut new_prover_transcript = Transcript::new(b"example");
@@ -403,6 +403,7 @@ mod test {
             &shape,
             &instance,
             witness,
+            &hint,
             &SRS,
             &mut new_prover_transcript,
         );
@@ -0,0 +1,176 @@
+//! Checkpoints of the running accumulator of a prover, so that a long-running IVC or aggregation
+//! process can be stopped and resumed, or moved to another machine.
+//!
+//! On disk a checkpoint is:
+//! - the 8 magic bytes `KZHACCUM`,
+//! - the format version [`CHECKPOINT_VERSION`] as a little-endian u32,
+//! - the dimension of the KZH scheme of the accumulator (2, 3 or 4) as a little-endian u32,
+//! - the number of folding steps as a little-endian u64,
+//! - the compressed canonical serialization of the accumulator.
+//!
+//! The version is bumped whenever the layout of the accumulators changes. The SRS is not part of
+//! the checkpoint, it is saved once with its own canonical serialization.
+
+use crate::kzh::errors::CheckpointError;
+use crate::kzh_fold::AccumulationScheme;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use std::marker::PhantomData;
+
+/// magic bytes at the start of every checkpoint
+pub const CHECKPOINT_MAGIC: [u8; 8] = *b"KZHACCUM";
+
+/// version of the checkpoint format
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint<E: Pairing, A: AccumulationScheme<E>>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    /// the number of folding steps the running accumulator went through
+    pub step: u64,
+    pub accumulator: A,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Pairing, A: AccumulationScheme<E>> Checkpoint<E, A>
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    pub fn new(step: u64, accumulator: A) -> Self {
+        Checkpoint {
+            step,
+            accumulator,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Writes the checkpoint in the format described in the module documentation
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), CheckpointError> {
+        writer.write_all(&CHECKPOINT_MAGIC).map_err(SerializationError::from)?;
+        CHECKPOINT_VERSION.serialize_compressed(&mut writer)?;
+        A::DIMENSION.serialize_compressed(&mut writer)?;
+        self.step.serialize_compressed(&mut writer)?;
+        self.accumulator.serialize_compressed(&mut writer)?;
+
+        Ok(())
+    }
+
+    /// Reads a checkpoint in the format described in the module documentation, the accumulator is
+    /// validated while deserialized and only returned if it is satisfied with respect to srs
+    pub fn load<R: Read>(mut reader: R, srs: &A::SRS) -> Result<Self, CheckpointError> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(SerializationError::from)?;
+        if magic != CHECKPOINT_MAGIC {
+            return Err(CheckpointError::InvalidMagic);
+        }
+
+        let version = u32::deserialize_compressed(&mut reader)?;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion(version));
+        }
+
+        let dimension = u32::deserialize_compressed(&mut reader)?;
+        if dimension != A::DIMENSION {
+            return Err(CheckpointError::DimensionMismatch { expected: A::DIMENSION, found: dimension });
+        }
+
+        let step = u64::deserialize_compressed(&mut reader)?;
+        let accumulator = A::deserialize_compressed(&mut reader)?;
+
+        if !A::has_valid_sizes(srs, &accumulator) || !A::decide(srs, &accumulator) {
+            return Err(CheckpointError::UnsatisfiedAccumulator);
+        }
+
+        Ok(Checkpoint::new(step, accumulator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::errors::CheckpointError;
+    use crate::kzh::kzh2::{KZH2, KZH2SRS};
+    use crate::kzh::kzh3::{KZH3, KZH3SRS};
+    use crate::kzh::KZH;
+    use crate::kzh_fold::checkpoint::{Checkpoint, CHECKPOINT_VERSION};
+    use crate::kzh_fold::kzh2_fold::{Acc2SRS, Accumulator2};
+    use crate::kzh_fold::kzh3_fold::Accumulator3;
+    use crate::transcript::transcript::Transcript;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    fn get_srs() -> Acc2SRS<E> {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(6, &mut thread_rng());
+        Accumulator2::setup(srs_pcs, &mut thread_rng())
+    }
+
+    #[test]
+    fn test_save_load() {
+        let srs = get_srs();
+        let checkpoint = Checkpoint::<E, Accumulator2<E>>::new(7, Accumulator2::rand(&srs, &mut thread_rng()).unwrap());
+
+        let mut bytes = vec![];
+        checkpoint.save(&mut bytes).unwrap();
+        assert_eq!(Checkpoint::<E, Accumulator2<E>>::load(bytes.as_slice(), &srs).unwrap(), checkpoint);
+
+        // the srs is saved separately and can be reloaded as well
+        let mut srs_bytes = vec![];
+        srs.serialize_compressed(&mut srs_bytes).unwrap();
+        let srs_loaded = Acc2SRS::<E>::deserialize_compressed(srs_bytes.as_slice()).unwrap();
+        assert_eq!(srs_loaded, srs);
+
+        // resume folding from the loaded accumulator
+        let running = Checkpoint::<E, Accumulator2<E>>::load(bytes.as_slice(), &srs_loaded).unwrap().accumulator;
+        let fresh = Accumulator2::rand(&srs_loaded, &mut thread_rng()).unwrap();
+        let (instance, witness, _Q) = Accumulator2::prove(&srs_loaded, &running, &fresh, &mut Transcript::new(b"new_transcript"));
+        assert!(Accumulator2::decide(&srs_loaded, &Accumulator2 { witness, instance }));
+    }
+
+    #[test]
+    fn test_invalid_checkpoints() {
+        let srs = get_srs();
+        let checkpoint = Checkpoint::<E, Accumulator2<E>>::new(1, Accumulator2::rand(&srs, &mut thread_rng()).unwrap());
+
+        let mut bytes = vec![];
+        checkpoint.save(&mut bytes).unwrap();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] ^= 1;
+        assert!(matches!(Checkpoint::<E, Accumulator2<E>>::load(wrong_magic.as_slice(), &srs), Err(CheckpointError::InvalidMagic)));
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[8..12].copy_from_slice(&(CHECKPOINT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Checkpoint::<E, Accumulator2<E>>::load(wrong_version.as_slice(), &srs),
+            Err(CheckpointError::UnsupportedVersion(version)) if version == CHECKPOINT_VERSION + 1
+        ));
+
+        // a KZH2 accumulator can't be loaded as a KZH3 one
+        let pcs_srs_3: KZH3SRS<E> = KZH3::setup(6, &mut thread_rng());
+        let srs_3 = Accumulator3::setup(pcs_srs_3, &mut thread_rng());
+        assert!(matches!(
+            Checkpoint::<E, Accumulator3<E>>::load(bytes.as_slice(), &srs_3),
+            Err(CheckpointError::DimensionMismatch { expected: 3, found: 2 })
+        ));
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(Checkpoint::<E, Accumulator2<E>>::load(truncated, &srs), Err(CheckpointError::SerializationError(_))));
+
+        // an accumulator that does not satisfy the srs is rejected
+        let mut accumulator = checkpoint.accumulator.clone();
+        accumulator.instance.z += F::from(1u64);
+        let mut bytes = vec![];
+        Checkpoint::<E, Accumulator2<E>>::new(1, accumulator).save(&mut bytes).unwrap();
+        assert!(matches!(Checkpoint::<E, Accumulator2<E>>::load(bytes.as_slice(), &srs), Err(CheckpointError::UnsatisfiedAccumulator)));
+
+        // as well as one that was folded with another srs of the same size
+        let other_srs = get_srs();
+        let mut bytes = vec![];
+        checkpoint.save(&mut bytes).unwrap();
+        assert!(matches!(Checkpoint::<E, Accumulator2<E>>::load(bytes.as_slice(), &other_srs), Err(CheckpointError::UnsatisfiedAccumulator)));
+    }
+}
@@ -1,11 +1,11 @@
 use ark_ff::PrimeField;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate};
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
-use crate::kzh_fold::generic_linear_combination;
+use crate::kzh_fold::{generic_linear_combination, weighted_sum};
 
-#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
 pub struct EqTree<F: PrimeField> {
     /// vector of length 2 * 2 ^ depth - 1
     pub nodes: Vec<F>,
@@ -144,6 +144,47 @@ impl<F: PrimeField> EqTree<F> {
             depth: tree1.depth,
         }
     }
+
+    /// Computes sum_i coeffs[i] * trees[i], used to fold several trees at once
+    pub fn weighted_sum(trees: &[&Self], coeffs: &[F]) -> Self {
+        assert!(
+            trees.iter().all(|tree| tree.depth == trees[0].depth),
+            "Trees must have the same depth for linear combination."
+        );
+
+        let nodes: Vec<&[F]> = trees.iter().map(|tree| tree.nodes.as_slice()).collect();
+
+        Self {
+            nodes: weighted_sum(nodes.as_slice(), coeffs),
+            depth: trees[0].depth,
+        }
+    }
+}
+
+impl<F: PrimeField> Valid for EqTree<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        // the number of nodes has to match the depth, otherwise indexing the tree would panic
+        if self.depth >= usize::BITS as usize - 1 || self.nodes.len() != 2 * (1 << self.depth) - 1 {
+            return Err(SerializationError::InvalidData);
+        }
+
+        self.nodes.check()
+    }
+}
+
+impl<F: PrimeField> CanonicalDeserialize for EqTree<F> {
+    fn deserialize_with_mode<R: Read>(mut reader: R, compress: Compress, validate: Validate) -> Result<Self, SerializationError> {
+        let tree = EqTree {
+            nodes: Vec::<F>::deserialize_with_mode(&mut reader, compress, validate)?,
+            depth: usize::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+
+        if let Validate::Yes = validate {
+            tree.check()?;
+        }
+
+        Ok(tree)
+    }
 }
 
 #[cfg(test)]
@@ -186,4 +227,21 @@ mod tests {
 
         assert_eq!(tree.get_leaves().to_vec(), results);
     }
+
+    #[test]
+    fn test_deserialize() {
+        let x: Vec<F> = (0..4).map(|_| F::rand(&mut thread_rng())).collect();
+        let tree = EqTree::new(x.as_slice());
+
+        let mut bytes = vec![];
+        tree.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(EqTree::deserialize_compressed(bytes.as_slice()).unwrap(), tree);
+
+        // a depth inconsistent with the number of nodes is rejected
+        let malformed = EqTree { nodes: tree.nodes.clone(), depth: 3 };
+        let mut bytes = vec![];
+        malformed.serialize_compressed(&mut bytes).unwrap();
+        assert!(EqTree::<F>::deserialize_compressed(bytes.as_slice()).is_err());
+        assert!(EqTree::<F>::deserialize_compressed_unchecked(bytes.as_slice()).is_ok());
+    }
 }
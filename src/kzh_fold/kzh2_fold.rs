@@ -3,26 +3,28 @@ use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::{AdditiveGroup, Field, PrimeField, Zero};
 use ark_poly::EvaluationDomain;
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
 use ark_std::{end_timer, start_timer};
 use rand::{Rng, RngCore};
 use rayon::iter::IndexedParallelIterator;
+use rayon::iter::IntoParallelIterator;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
 use std::ops::{Add, Mul, Neg, Sub};
 
 use crate::kzh_fold::eq_tree::EqTree;
-use crate::kzh_fold::{generate_random_elements, generic_linear_combination};
+use crate::kzh_fold::{AccumulationScheme, derive_generators, generate_random_elements, generic_linear_combination, lagrange_basis_at, vanishing_polynomial_at, weighted_sum};
 use crate::gadgets::non_native::util::convert_affine_to_scalars;
+use crate::kzh::errors::{FoldingError, PCSError, SRSError};
 use crate::kzh::KZH;
 use crate::math::Math;
-use crate::kzh::kzh2::{KZH2, KZH2Opening, KZH2SRS};
+use crate::kzh::kzh2::{KZH2, KZH2Commitment, KZH2Opening, KZH2PreparedVerifierKey, KZH2SRS};
 use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
 use crate::transcript::transcript::{AppendToTranscript, Transcript};
 use crate::utils::inner_product;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Acc2SRS<E: Pairing> {
     // vector of size 2 * degree_x - 1
     pub k_x: Vec<E::G1Affine>,
@@ -34,7 +36,49 @@ pub struct Acc2SRS<E: Pairing> {
     pub pc_srs: KZH2SRS<E>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+impl<E: Pairing> Acc2SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the accumulator SRS for a smaller maximum degree, see KZH2SRS::trim
+    pub fn trim(&self, maximum_degree: usize) -> Result<Acc2SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim(maximum_degree)?))
+    }
+
+    /// Derives the accumulator SRS for the given log degrees, see KZH2SRS::trim_with_degrees
+    pub fn trim_with_degrees(&self, degrees: (usize, usize)) -> Result<Acc2SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim_with_degrees(degrees)?))
+    }
+
+    /// The bases k_x and k_y are independent so they are simply truncated to the sizes of the
+    /// trees of the trimmed pc_srs
+    fn trim_bases(&self, pc_srs: KZH2SRS<E>) -> Acc2SRS<E> {
+        Acc2SRS {
+            k_x: self.k_x[..2 * pc_srs.degree_x - 1].to_vec(),
+            k_y: self.k_y[..2 * pc_srs.degree_y - 1].to_vec(),
+            k_prime: self.k_prime,
+            pc_srs,
+        }
+    }
+
+    /// Checks that k_x, k_y and k_prime are the generators derived from `label` by
+    /// Accumulator2::setup_transparent, so that nobody knows discrete-log relations among them
+    pub fn check_generators(&self, label: &[u8]) -> Result<(), SRSError> {
+        if self.k_x.len() != 2 * self.pc_srs.degree_x - 1
+            || self.k_x != derive_generators::<E>(label, b"k_x", self.k_x.len())
+            || self.k_y.len() != 2 * self.pc_srs.degree_y - 1
+            || self.k_y != derive_generators::<E>(label, b"k_y", self.k_y.len())
+            || self.k_prime != derive_generators::<E>(label, b"k_prime", 1)[0]
+        {
+            return Err(SRSError::InvalidGenerators);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Acc2Instance<E: Pairing> {
     pub C: E::G1Affine,
     pub T: E::G1Affine,
@@ -77,7 +121,7 @@ where
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Acc2Witness<E: Pairing> {
     /// size of degree_x
     pub D_x: Vec<E::G1Affine>,
@@ -90,7 +134,7 @@ pub struct Acc2Witness<E: Pairing> {
     pub tree_y: EqTree<E::ScalarField>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Accumulator2<E: Pairing> {
     pub witness: Acc2Witness<E>,
     pub instance: Acc2Instance<E>,
@@ -110,6 +154,17 @@ where
         }
     }
 
+    /// Same as `setup` but k_x, k_y and k_prime are derived from the domain-separation `label` instead
+    /// of sampled by the caller, anyone can then check them with Acc2SRS::check_generators
+    pub fn setup_transparent(pc_srs: KZH2SRS<E>, label: &[u8]) -> Acc2SRS<E> {
+        Acc2SRS {
+            pc_srs: pc_srs.clone(),
+            k_x: derive_generators::<E>(label, b"k_x", 2 * pc_srs.degree_x - 1),
+            k_y: derive_generators::<E>(label, b"k_y", 2 * pc_srs.degree_y - 1),
+            k_prime: derive_generators::<E>(label, b"k_prime", 1)[0],
+        }
+    }
+
     pub fn new(instance: &Acc2Instance<E>, witness: &Acc2Witness<E>) -> Accumulator2<E> {
         Accumulator2 {
             witness: witness.clone(),
@@ -352,13 +407,171 @@ where
         res.mul(minus_one_over_two).into()
     }
 
+    /// the k-to-1 version of compute_fiat_shamir_challenge, hashing the k accumulator instances and the k - 1 error terms Q
+    pub fn compute_multi_fiat_shamir_challenge(transcript: &mut Transcript<E::ScalarField>, instances: &[Acc2Instance<E>], Q: &[E::G1Affine]) -> E::ScalarField {
+        // add the instances to the transcript
+        for instance in instances {
+            transcript.append_scalars(b"instance", instance.to_sponge_field_elements().as_slice());
+        }
+
+        // convert the proof Q into scalar field elements and add to the transcript
+        for q in Q {
+            let (p1, p2) = convert_affine_to_scalars::<E>(*q);
+            transcript.append_scalars(b"Q", &[p1, p2]);
+        }
+
+        // return the challenge
+        transcript.challenge_scalar(b"challenge scalar")
+    }
+
+    /// Folds k accumulators at once. The accumulators are seen as the evaluations over {0, ..., k - 1} of
+    /// acc(t) = sum_i L_i(t) * acc_i and the folded accumulator is acc(beta). Since the decider is quadratic,
+    /// dec(acc(t)) - sum_i L_i(t) * E_i vanishes on {0, ..., k - 1}, its quotient K(t) by t (t - 1) ... (t - k + 1)
+    /// has degree k - 2 and the proof is given by the k - 1 evaluations K(k), ..., K(2k - 2)
+    pub fn prove_multi(
+        srs: &Acc2SRS<E>,
+        accs: &[Accumulator2<E>],
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Acc2Instance<E>, Acc2Witness<E>, Vec<E::G1Affine>) {
+        assert!(accs.len() >= 2, "at least two accumulators are needed to fold");
+
+        let instances: Vec<Acc2Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+        // compute the error terms Q
+        let Q = Self::compute_multi_error_terms(srs, accs);
+
+        // the transcript is cloned for the same reason as in Self::prove()
+        let mut transcript_clone = transcript.clone();
+
+        // get challenge beta and the coefficients L_i(beta)
+        let beta = Accumulator2::compute_multi_fiat_shamir_challenge(transcript, instances.as_slice(), Q.as_slice());
+        let coeffs = lagrange_basis_at(0, accs.len(), beta);
+
+        // get the accumulated new_instance
+        let new_instance = Self::verify_multi(srs, instances.as_slice(), Q.as_slice(), &mut transcript_clone)
+            .expect("the prover computes one error term per accumulator but one");
+
+        // get the accumulated witness
+        let new_witness = Self::combine_witnesses(accs, coeffs.as_slice());
+
+        (new_instance, new_witness, Q)
+    }
+
+    pub fn verify_multi(
+        _srs: &Acc2SRS<E>,
+        instances: &[Acc2Instance<E>],
+        Q: &[E::G1Affine],
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Result<Acc2Instance<E>, FoldingError> {
+        let k = instances.len();
+        if k < 2 {
+            return Err(FoldingError::TooFewAccumulators(k));
+        }
+        if Q.len() != k - 1 {
+            return Err(FoldingError::InvalidErrorTermCount { expected: k - 1, found: Q.len() });
+        }
+
+        // compute the fiat-shamir challenge
+        let beta = Accumulator2::compute_multi_fiat_shamir_challenge(transcript, instances, Q);
+        let coeffs = lagrange_basis_at(0, k, beta);
+
+        let mut new_instance = Self::combine_instances(instances, coeffs.as_slice());
+
+        // E = sum_i L_i(beta) * E_i + Z(beta) * K(beta), where K is interpolated from Q over {k, ..., 2k - 2}
+        let vanishing = vanishing_polynomial_at(k, beta);
+        let Q_coeffs: Vec<E::ScalarField> = lagrange_basis_at(k, k - 1, beta)
+            .into_iter()
+            .map(|l| l * vanishing)
+            .collect();
+        new_instance.E = (new_instance.E.into_group() + E::G1::msm_unchecked(Q, Q_coeffs.as_slice())).into();
+
+        Ok(new_instance)
+    }
+
+    /// returns the k - 1 error terms Q_j = K(k + j) for the multi-folding of the k accumulators
+    pub fn compute_multi_error_terms(srs: &Acc2SRS<E>, accs: &[Accumulator2<E>]) -> Vec<E::G1Affine> {
+        let k = accs.len();
+        let instances: Vec<Acc2Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+        let Q: Vec<E::G1> = (k..2 * k - 1)
+            .map(|t| {
+                let t = E::ScalarField::from(t as u64);
+                let coeffs = lagrange_basis_at(0, k, t);
+
+                // acc(t), its error term is sum_i L_i(t) * E_i
+                let acc = Accumulator2 {
+                    witness: Self::combine_witnesses(accs, coeffs.as_slice()),
+                    instance: Self::combine_instances(instances.as_slice(), coeffs.as_slice()),
+                };
+
+                let res = Self::helper_function_decide(srs, &acc).into_group().sub(acc.instance.E);
+                res.mul(vanishing_polynomial_at(k, t).inverse().unwrap())
+            })
+            .collect();
+
+        E::G1::normalize_batch(Q.as_slice())
+    }
+
+    /// returns the instance sum_i coeffs[i] * instances[i], the error term included
+    fn combine_instances(instances: &[Acc2Instance<E>], coeffs: &[E::ScalarField]) -> Acc2Instance<E> {
+        let commitments: Vec<E::G1Affine> = instances.iter().map(|instance| instance.C).collect();
+        let T_terms: Vec<E::G1Affine> = instances.iter().map(|instance| instance.T).collect();
+        let errors: Vec<E::G1Affine> = instances.iter().map(|instance| instance.E).collect();
+        let x: Vec<&[E::ScalarField]> = instances.iter().map(|instance| instance.x.as_slice()).collect();
+        let y: Vec<&[E::ScalarField]> = instances.iter().map(|instance| instance.y.as_slice()).collect();
+
+        Acc2Instance {
+            C: E::G1::msm_unchecked(commitments.as_slice(), coeffs).into(),
+            T: E::G1::msm_unchecked(T_terms.as_slice(), coeffs).into(),
+            E: E::G1::msm_unchecked(errors.as_slice(), coeffs).into(),
+            x: weighted_sum(x.as_slice(), coeffs),
+            y: weighted_sum(y.as_slice(), coeffs),
+            z: instances.iter().zip(coeffs.iter()).map(|(instance, c)| instance.z * c).sum(),
+        }
+    }
+
+    /// returns the witness sum_i coeffs[i] * accs[i].witness
+    fn combine_witnesses(accs: &[Accumulator2<E>], coeffs: &[E::ScalarField]) -> Acc2Witness<E> {
+        assert!(accs.iter().all(|acc| acc.witness.D_x.len() == accs[0].witness.D_x.len()));
+
+        let D_x: Vec<E::G1> = (0..accs[0].witness.D_x.len())
+            .into_par_iter()
+            .map(|j| {
+                let bases: Vec<E::G1Affine> = accs.iter().map(|acc| acc.witness.D_x[j]).collect();
+                E::G1::msm_unchecked(bases.as_slice(), coeffs)
+            })
+            .collect();
+
+        Acc2Witness {
+            D_x: E::G1::normalize_batch(D_x.as_slice()),
+            f_star_poly: MultilinearPolynomial::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.f_star_poly).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+            tree_x: EqTree::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.tree_x).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+            tree_y: EqTree::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.tree_y).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+        }
+    }
+
     pub fn decide(srs: &Acc2SRS<E>, acc: &Accumulator2<E>) -> bool {
+        Self::decide_prepared(srs, srs.pc_srs.prepared_verifier_key(), acc)
+    }
+
+    /// Same as `decide` but with the G2 elements of the SRS already prepared, which avoids
+    /// preparing them again when deciding many accumulators
+    pub fn decide_prepared(srs: &Acc2SRS<E>, pvk: &KZH2PreparedVerifierKey<E>, acc: &Accumulator2<E>) -> bool {
         let instance = &acc.instance;
         let witness = &acc.witness;
 
         // first condition
-        let pairing_lhs = E::multi_pairing(&witness.D_x, &srs.pc_srs.V_x);
-        let pairing_rhs = E::pairing(instance.C, srs.pc_srs.V_prime);
+        let pairing_lhs = E::multi_pairing(&witness.D_x, pvk.prepared_V_x.iter().cloned());
+        let pairing_rhs = E::pairing(instance.C, pvk.prepared_V_prime.clone());
 
         // second condition
         let ip_rhs = instance.T;
@@ -437,10 +650,91 @@ where
     }
 }
 
+impl<E: Pairing> AccumulationScheme<E> for Accumulator2<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
+{
+    const DIMENSION: u32 = 2;
+
+    type PCS = KZH2<E>;
+    type SRS = Acc2SRS<E>;
+    type Instance = Acc2Instance<E>;
+    type Witness = Acc2Witness<E>;
+    type Proof = E::G1Affine;
+
+    fn setup<R: RngCore>(pc_srs: KZH2SRS<E>, rng: &mut R) -> Acc2SRS<E> {
+        Accumulator2::setup(pc_srs, rng)
+    }
+
+    fn from_opening(
+        srs: &Acc2SRS<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH2Commitment<E>,
+        open: KZH2Opening<E>,
+    ) -> Accumulator2<E> {
+        let split_input = KZH2::split_input(&srs.pc_srs, input, E::ScalarField::ZERO);
+
+        let instance = Accumulator2::proof_to_accumulator_instance(srs, &com.C, &split_input[0], &split_input[1], output);
+        let witness = Accumulator2::proof_to_accumulator_witness(srs, open, &split_input[0], &split_input[1]);
+
+        Accumulator2 { witness, instance }
+    }
+
+    fn instance(&self) -> &Acc2Instance<E> {
+        &self.instance
+    }
+
+    fn witness(&self) -> &Acc2Witness<E> {
+        &self.witness
+    }
+
+    fn prove(
+        srs: &Acc2SRS<E>,
+        acc_1: &Accumulator2<E>,
+        acc_2: &Accumulator2<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Accumulator2<E>, E::G1Affine) {
+        let (instance, witness, proof) = Accumulator2::prove(srs, acc_1, acc_2, transcript);
+
+        (Accumulator2 { witness, instance }, proof)
+    }
+
+    fn verify(
+        srs: &Acc2SRS<E>,
+        instance_1: &Acc2Instance<E>,
+        instance_2: &Acc2Instance<E>,
+        proof: &E::G1Affine,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Acc2Instance<E> {
+        Accumulator2::verify(srs, instance_1, instance_2, *proof, transcript)
+    }
+
+    fn decide(srs: &Acc2SRS<E>, acc: &Accumulator2<E>) -> bool {
+        Accumulator2::decide(srs, acc)
+    }
+
+    fn has_valid_sizes(srs: &Acc2SRS<E>, acc: &Accumulator2<E>) -> bool {
+        let (instance, witness) = (&acc.instance, &acc.witness);
+        let (degree_x, degree_y) = (srs.pc_srs.degree_x, srs.pc_srs.degree_y);
+
+        instance.x.len() == degree_x.log_2()
+            && instance.y.len() == degree_y.log_2()
+            && witness.D_x.len() == degree_x
+            && witness.f_star_poly.evaluation_over_boolean_hypercube.len() == degree_y
+            && witness.f_star_poly.num_variables == degree_y.log_2()
+            && witness.tree_x.depth == degree_x.log_2()
+            && witness.tree_y.depth == degree_y.log_2()
+            && srs.k_x.len() == witness.tree_x.nodes.len()
+            && srs.k_y.len() == witness.tree_y.nodes.len()
+    }
+}
+
 impl<E: Pairing> Accumulator2<E> {
     /// this function returns a random satisfying accumulator by generating two random frseh accumualtors (KZH openings)
     /// and then accumulating them, so that the error vector wouldn't be zero
-    pub fn rand<R: RngCore>(srs: &Acc2SRS<E>, rng: &mut R) -> Accumulator2<E>
+    pub fn rand<R: RngCore>(srs: &Acc2SRS<E>, rng: &mut R) -> Result<Accumulator2<E>, PCSError>
     where
         <E as Pairing>::ScalarField: Absorb,
         <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
@@ -481,16 +775,16 @@ impl<E: Pairing> Accumulator2<E> {
         let z2 = polynomial2.evaluate(&input_2);
 
         // commit to the polynomial
-        let com1 = KZH2::commit(&srs.pc_srs, &polynomial1);
-        let com2 = KZH2::commit(&srs.pc_srs, &polynomial2);
+        let (com1, hint1) = KZH2::commit(&srs.pc_srs, &polynomial1);
+        let (com2, hint2) = KZH2::commit(&srs.pc_srs, &polynomial2);
 
         // open the commitment
-        let open1 = KZH2::open(&srs.pc_srs, input_1.as_slice(), &com1, &polynomial1);
-        let open2 = KZH2::open(&srs.pc_srs, input_2.as_slice(), &com2, &polynomial2);
+        let open1 = KZH2::open(&srs.pc_srs, input_1.as_slice(), &hint1, &polynomial1);
+        let open2 = KZH2::open(&srs.pc_srs, input_2.as_slice(), &hint2, &polynomial2);
 
         // verify the proof
-        KZH2::verify(&srs.pc_srs, &input_1, &z1, &com1, &open1);
-        KZH2::verify(&srs.pc_srs, &input_2, &z2, &com2, &open2);
+        KZH2::verify(&srs.pc_srs, &input_1, &z1, &com1, &open1)?;
+        KZH2::verify(&srs.pc_srs, &input_2, &z2, &com2, &open2)?;
 
         let instance1 = Accumulator2::proof_to_accumulator_instance(&srs, &com1.C, &x1, &y1, &z1);
         let witness1 = Accumulator2::proof_to_accumulator_witness(&srs, open1, &x1, &y1);
@@ -513,7 +807,7 @@ impl<E: Pairing> Accumulator2<E> {
         // verify the accumulated instance is satisfied
         debug_assert!(Accumulator2::decide(&srs, &accumulated_acc));
 
-        accumulated_acc
+        Ok(accumulated_acc)
     }
 }
 
@@ -531,8 +825,8 @@ pub mod test {
         let srs_pcs: KZH2SRS<E> = KZH2::setup((degree_x * degree_y).log_2(), &mut thread_rng());
         let srs = Accumulator2::setup(srs_pcs.clone(), &mut thread_rng());
 
-        let acc1 = Accumulator2::rand(&srs, &mut thread_rng());
-        let acc2 = Accumulator2::rand(&srs, &mut thread_rng());
+        let acc1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
 
         let mut prover_transcript = Transcript::new(b"new_transcript");
         let mut verifier_transcript = prover_transcript.clone();
@@ -548,6 +842,135 @@ pub mod test {
         end_timer!(decide_timer);
     }
 
+    #[test]
+    fn test_decide_prepared() {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(6, &mut thread_rng());
+        let srs = Accumulator2::setup(srs_pcs, &mut thread_rng());
+        let pvk = KZH2PreparedVerifierKey::prepare(&srs.pc_srs);
+
+        let acc1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        assert!(Accumulator2::decide_prepared(&srs, &pvk, &acc1));
+        assert!(Accumulator2::decide_prepared(&srs, &pvk, &acc2));
+
+        let (instance, witness, _Q) = Accumulator2::prove(&srs, &acc1, &acc2, &mut Transcript::new(b"new_transcript"));
+        assert!(Accumulator2::decide_prepared(&srs, &pvk, &Accumulator2 { witness, instance }));
+    }
+
+    #[test]
+    fn test_trimmed_srs() {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(10, &mut thread_rng());
+        let srs = Accumulator2::setup(srs_pcs, &mut thread_rng()).trim(6).unwrap();
+        assert!(matches!(srs.trim(7), Err(SRSError::TrimmedDegreeTooLarge)));
+
+        let acc1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+
+        let mut prover_transcript = Transcript::new(b"new_transcript");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let (instance, witness, Q) = Accumulator2::prove(&srs, &acc1, &acc2, &mut prover_transcript);
+        let instance_expected = Accumulator2::verify(&srs, &acc1.instance, &acc2.instance, Q, &mut verifier_transcript);
+        assert_eq!(instance, instance_expected);
+
+        assert!(Accumulator2::decide(&srs, &Accumulator2 { witness, instance }));
+    }
+
+    #[test]
+    fn test_transparent_setup() {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(8, &mut thread_rng());
+        let srs = Accumulator2::setup_transparent(srs_pcs.clone(), b"kzh2 accumulator");
+
+        // the generators are deterministic and can be checked by anyone
+        let srs_expected = Accumulator2::setup_transparent(srs_pcs.clone(), b"kzh2 accumulator");
+        assert_eq!((&srs.k_x, &srs.k_y, srs.k_prime), (&srs_expected.k_x, &srs_expected.k_y, srs_expected.k_prime));
+        srs.check_generators(b"kzh2 accumulator").unwrap();
+        srs.trim(6).unwrap().check_generators(b"kzh2 accumulator").unwrap();
+
+        assert!(matches!(srs.check_generators(b"another label"), Err(SRSError::InvalidGenerators)));
+        let random_srs = Accumulator2::setup(srs_pcs, &mut thread_rng());
+        assert!(matches!(random_srs.check_generators(b"kzh2 accumulator"), Err(SRSError::InvalidGenerators)));
+
+        let acc1 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let acc2 = Accumulator2::rand(&srs, &mut thread_rng()).unwrap();
+        let (instance, witness, _Q) = Accumulator2::prove(&srs, &acc1, &acc2, &mut Transcript::new(b"new_transcript"));
+        assert!(Accumulator2::decide(&srs, &Accumulator2 { witness, instance }));
+    }
+
+    #[test]
+    fn test_multi_fold() {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(6, &mut thread_rng());
+        let srs = Accumulator2::setup(srs_pcs, &mut thread_rng());
+
+        for k in [2, 3, 4] {
+            let accs: Vec<Accumulator2<E>> = (0..k)
+                .map(|_| Accumulator2::rand(&srs, &mut thread_rng()).unwrap())
+                .collect();
+            let instances: Vec<Acc2Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+            let mut prover_transcript = Transcript::new(b"new_transcript");
+            let mut verifier_transcript = prover_transcript.clone();
+
+            let (instance, witness, Q) = Accumulator2::prove_multi(&srs, accs.as_slice(), &mut prover_transcript);
+            assert_eq!(Q.len(), k - 1);
+
+            let instance_expected = Accumulator2::verify_multi(&srs, instances.as_slice(), Q.as_slice(), &mut verifier_transcript).unwrap();
+            assert_eq!(instance, instance_expected);
+
+            assert!(Accumulator2::decide(&srs, &Accumulator2 { witness, instance }));
+        }
+    }
+
+    #[test]
+    fn test_multi_fold_against_pairwise() {
+        let srs_pcs: KZH2SRS<E> = KZH2::setup(6, &mut thread_rng());
+        let srs = Accumulator2::setup(srs_pcs, &mut thread_rng());
+
+        let accs: Vec<Accumulator2<E>> = (0..3)
+            .map(|_| Accumulator2::rand(&srs, &mut thread_rng()).unwrap())
+            .collect();
+
+        // fold the accumulators one by one with the pairwise folding
+        let mut transcript = Transcript::new(b"new_transcript");
+        let mut chained = accs[0].clone();
+        for acc in &accs[1..] {
+            let (instance, witness, _Q) = Accumulator2::prove(&srs, &chained, acc, &mut transcript);
+            chained = Accumulator2 { witness, instance };
+        }
+        assert!(Accumulator2::decide(&srs, &chained));
+
+        // fold them in a single round
+        let (instance, witness, Q) = Accumulator2::prove_multi(&srs, accs.as_slice(), &mut Transcript::new(b"new_transcript"));
+        assert!(Accumulator2::decide(&srs, &Accumulator2 { witness: witness.clone(), instance }));
+
+        // the instance folded by the verifier with a tampered error term doesn't match the witness
+        let instances: Vec<Acc2Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+        let mut tampered_Q = Q.clone();
+        tampered_Q[1] = (tampered_Q[1] + E::G1Affine::generator()).into_affine();
+        let instance = Accumulator2::verify_multi(&srs, instances.as_slice(), tampered_Q.as_slice(), &mut Transcript::new(b"new_transcript")).unwrap();
+        assert!(!Accumulator2::decide(&srs, &Accumulator2 { witness, instance }));
+
+        // the verifier rejects a proof without one error term per accumulator but one
+        assert_eq!(
+            Accumulator2::verify_multi(&srs, instances.as_slice(), &Q[..1], &mut Transcript::new(b"new_transcript")),
+            Err(FoldingError::InvalidErrorTermCount { expected: 2, found: 1 })
+        );
+        let mut extended_Q = Q.clone();
+        extended_Q.push(E::G1Affine::generator());
+        assert_eq!(
+            Accumulator2::verify_multi(&srs, instances.as_slice(), extended_Q.as_slice(), &mut Transcript::new(b"new_transcript")),
+            Err(FoldingError::InvalidErrorTermCount { expected: 2, found: 3 })
+        );
+        assert_eq!(
+            Accumulator2::verify_multi(&srs, &instances[..1], &[], &mut Transcript::new(b"new_transcript")),
+            Err(FoldingError::TooFewAccumulators(1))
+        );
+
+        // for two accumulators, the quotient of the multi-folding is K(2) = -Q
+        let Q = Accumulator2::helper_function_Q(&srs, &accs[0], &accs[1]);
+        assert_eq!(Accumulator2::compute_multi_error_terms(&srs, &accs[..2]), vec![Q.neg()]);
+    }
+
     #[test]
     fn test_accumulator_sizes() {
         // change the degrees later, it takes too long
@@ -559,7 +982,7 @@ pub mod test {
             let srs_pcs: KZH2SRS<E> = KZH2::setup((degree_x * degree_y).log_2(), rng);
             let srs_acc = Accumulator2::setup(srs_pcs.clone(), rng);
             // get random accumulator
-            let acc = Accumulator2::rand(&srs_acc, rng);
+            let acc = Accumulator2::rand(&srs_acc, rng).unwrap();
 
             let witness_len = degree_x * degree_y;
             let witness_polynomial: MultilinearPolynomial<ScalarField> = MultilinearPolynomial::rand(
@@ -1,8 +1,9 @@
 use crate::gadgets::non_native::util::convert_affine_to_scalars;
+use crate::kzh::errors::{FoldingError, SRSError};
 use crate::kzh::kzh3::{KZH3Commitment, KZH3Opening, KZH3, KZH3SRS};
 use crate::kzh::KZH;
 use crate::kzh_fold::eq_tree::EqTree;
-use crate::kzh_fold::{generate_random_elements, generic_linear_combination};
+use crate::kzh_fold::{AccumulationScheme, derive_generators, generate_random_elements, generic_linear_combination, lagrange_basis_at, vanishing_polynomial_at, weighted_sum, weighted_sum_of_points};
 use crate::math::Math;
 use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
 use crate::transcript::transcript::Transcript;
@@ -57,7 +58,7 @@ impl<E: Pairing> Acc3Error<E> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Acc3SRS<E: Pairing> {
     // vector of size 2 * degree_x - 1
     pub k_x: Vec<E::G1Affine>,
@@ -71,6 +72,51 @@ pub struct Acc3SRS<E: Pairing> {
     pub pc_srs: KZH3SRS<E>,
 }
 
+impl<E: Pairing> Acc3SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the accumulator SRS for a smaller maximum degree, see KZH3SRS::trim
+    pub fn trim(&self, maximum_degree: usize) -> Result<Acc3SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim(maximum_degree)?))
+    }
+
+    /// Derives the accumulator SRS for the given log degrees, see KZH3SRS::trim_with_degrees
+    pub fn trim_with_degrees(&self, degrees: (usize, usize, usize)) -> Result<Acc3SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim_with_degrees(degrees)?))
+    }
+
+    /// The bases k_x, k_y and k_z are independent so they are simply truncated to the sizes of the
+    /// trees of the trimmed pc_srs
+    fn trim_bases(&self, pc_srs: KZH3SRS<E>) -> Acc3SRS<E> {
+        Acc3SRS {
+            k_x: self.k_x[..2 * pc_srs.degree_x - 1].to_vec(),
+            k_y: self.k_y[..2 * pc_srs.degree_y - 1].to_vec(),
+            k_z: self.k_z[..2 * pc_srs.degree_z - 1].to_vec(),
+            k_prime: self.k_prime,
+            pc_srs,
+        }
+    }
+
+    /// Checks that k_x, k_y, k_z and k_prime are the generators derived from `label` by
+    /// Accumulator3::setup_transparent, so that nobody knows discrete-log relations among them
+    pub fn check_generators(&self, label: &[u8]) -> Result<(), SRSError> {
+        if self.k_x.len() != 2 * self.pc_srs.degree_x - 1
+            || self.k_x != derive_generators::<E>(label, b"k_x", self.k_x.len())
+            || self.k_y.len() != 2 * self.pc_srs.degree_y - 1
+            || self.k_y != derive_generators::<E>(label, b"k_y", self.k_y.len())
+            || self.k_z.len() != 2 * self.pc_srs.degree_z - 1
+            || self.k_z != derive_generators::<E>(label, b"k_z", self.k_z.len())
+            || self.k_prime != derive_generators::<E>(label, b"k_prime", 1)[0]
+        {
+            return Err(SRSError::InvalidGenerators);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Acc3Instance<E: Pairing> {
     pub C: E::G1Affine,
@@ -134,7 +180,7 @@ pub struct Acc3Witness<E: Pairing> {
     pub f_star: MultilinearPolynomial<E::ScalarField>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize)]
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Accumulator3<E: Pairing> {
     pub witness: Acc3Witness<E>,
     pub instance: Acc3Instance<E>,
@@ -155,6 +201,18 @@ where
         }
     }
 
+    /// Same as `setup` but k_x, k_y, k_z and k_prime are derived from the domain-separation `label` instead
+    /// of sampled by the caller, anyone can then check them with Acc3SRS::check_generators
+    pub fn setup_transparent(pc_srs: KZH3SRS<E>, label: &[u8]) -> Acc3SRS<E> {
+        Acc3SRS {
+            pc_srs: pc_srs.clone(),
+            k_x: derive_generators::<E>(label, b"k_x", 2 * pc_srs.degree_x - 1),
+            k_y: derive_generators::<E>(label, b"k_y", 2 * pc_srs.degree_y - 1),
+            k_z: derive_generators::<E>(label, b"k_z", 2 * pc_srs.degree_z - 1),
+            k_prime: derive_generators::<E>(label, b"k_prime", 1)[0],
+        }
+    }
+
     pub fn new(instance: &Acc3Instance<E>, witness: &Acc3Witness<E>) -> Accumulator3<E> {
         Accumulator3 {
             witness: witness.clone(),
@@ -182,6 +240,27 @@ where
         // return the challenge
         transcript.challenge_scalar(b"challenge scalar")
     }
+
+    /// the k-to-1 version of compute_fiat_shamir_challenge, hashing the k accumulator instances and the k - 1 error terms
+    pub fn compute_multi_fiat_shamir_challenge(
+        transcript: &mut Transcript<E::ScalarField>,
+        instances: &[Acc3Instance<E>],
+        proof: &[Acc3Error<E>],
+    ) -> E::ScalarField {
+        // add the instances to the transcript
+        for instance in instances {
+            transcript.append_scalars(b"instance", instance.to_sponge_field_elements().as_slice());
+        }
+
+        // convert the error terms into scalar field elements and add to the transcript
+        for E in proof.iter().flat_map(|error| error.to_vec()) {
+            let (p1, p2) = convert_affine_to_scalars::<E>(E);
+            transcript.append_scalars(b"proof", &[p1, p2]);
+        }
+
+        // return the challenge
+        transcript.challenge_scalar(b"challenge scalar")
+    }
 }
 
 // impl function to convert proof into accumulator
@@ -230,7 +309,6 @@ impl<E: Pairing> Accumulator3<E> {
 
     pub fn proof_to_accumulator_witness(
         srs: &Acc3SRS<E>,
-        com: KZH3Commitment<E>,
         proof: KZH3Opening<E>,
         input: &[E::ScalarField],
     ) -> Acc3Witness<E>
@@ -242,7 +320,7 @@ impl<E: Pairing> Accumulator3<E> {
         let split_input = KZH3::split_input(&srs.pc_srs, input, E::ScalarField::ZERO);
 
         Acc3Witness {
-            D_x: com.D_x,
+            D_x: proof.D_x,
             D_y: proof.D_y,
             tree_x: EqTree::new(split_input[0].as_slice()),
             tree_y: EqTree::new(split_input[1].as_slice()),
@@ -471,6 +549,155 @@ impl<E: Pairing> Accumulator3<E> {
     }
 }
 
+// k-to-1 folding functions
+impl<E: Pairing> Accumulator3<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
+{
+    /// Folds k accumulators at once. The accumulators are seen as the evaluations over {0, ..., k - 1} of
+    /// acc(t) = sum_i L_i(t) * acc_i and the folded accumulator is acc(beta). Since dec is quadratic,
+    /// dec(acc(t)) - sum_i L_i(t) * E_i vanishes on {0, ..., k - 1}, its quotient K(t) by t (t - 1) ... (t - k + 1)
+    /// has degree k - 2 and the proof is given by the k - 1 evaluations K(k), ..., K(2k - 2)
+    pub fn prove_multi(
+        srs: &Acc3SRS<E>,
+        accs: &[Accumulator3<E>],
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Acc3Instance<E>, Acc3Witness<E>, Vec<Acc3Error<E>>) {
+        assert!(accs.len() >= 2, "at least two accumulators are needed to fold");
+
+        let instances: Vec<Acc3Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+        // compute the error terms
+        let proof = Self::compute_multi_error_terms(srs, accs);
+
+        // the transcript is cloned for the same reason as in Self::prove()
+        let mut transcript_clone = transcript.clone();
+
+        // get challenge beta and the coefficients L_i(beta)
+        let beta = Accumulator3::compute_multi_fiat_shamir_challenge(transcript, instances.as_slice(), proof.as_slice());
+        let coeffs = lagrange_basis_at(0, accs.len(), beta);
+
+        // get the accumulated new_instance
+        let new_instance = Self::verify_multi(srs, instances.as_slice(), proof.as_slice(), &mut transcript_clone)
+            .expect("the prover computes one error term per accumulator but one");
+
+        // get the accumulated witness
+        let new_witness = Self::combine_witnesses(accs, coeffs.as_slice());
+
+        (new_instance, new_witness, proof)
+    }
+
+    pub fn verify_multi(
+        _srs: &Acc3SRS<E>,
+        instances: &[Acc3Instance<E>],
+        proof: &[Acc3Error<E>],
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Result<Acc3Instance<E>, FoldingError> {
+        let k = instances.len();
+        if k < 2 {
+            return Err(FoldingError::TooFewAccumulators(k));
+        }
+        if proof.len() != k - 1 {
+            return Err(FoldingError::InvalidErrorTermCount { expected: k - 1, found: proof.len() });
+        }
+
+        let beta = Accumulator3::compute_multi_fiat_shamir_challenge(transcript, instances, proof);
+        let coeffs = lagrange_basis_at(0, k, beta);
+
+        let mut new_instance = Self::combine_instances(instances, coeffs.as_slice());
+
+        // E = sum_i L_i(beta) * E_i + Z(beta) * K(beta), where K is interpolated from the proof over {k, ..., 2k - 2}
+        let vanishing = vanishing_polynomial_at(k, beta);
+        let proof_coeffs: Vec<E::ScalarField> = lagrange_basis_at(k, k - 1, beta)
+            .into_iter()
+            .map(|l| l * vanishing)
+            .collect();
+        let proof_terms: Vec<E::G1Affine> = proof.iter().map(|error| error.E).collect();
+        new_instance.E = Acc3Error {
+            E: (new_instance.E.E.into_group() + E::G1::msm_unchecked(proof_terms.as_slice(), proof_coeffs.as_slice())).into(),
+        };
+
+        Ok(new_instance)
+    }
+
+    /// returns the k - 1 error terms K(k + j) for the multi-folding of the k accumulators
+    pub fn compute_multi_error_terms(srs: &Acc3SRS<E>, accs: &[Accumulator3<E>]) -> Vec<Acc3Error<E>> {
+        let k = accs.len();
+        let instances: Vec<Acc3Instance<E>> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+        let errors: Vec<E::G1> = (k..2 * k - 1)
+            .map(|t| {
+                let t = E::ScalarField::from(t as u64);
+                let coeffs = lagrange_basis_at(0, k, t);
+
+                // acc(t), its error term is sum_i L_i(t) * E_i
+                let acc = Accumulator3::new(
+                    &Self::combine_instances(instances.as_slice(), coeffs.as_slice()),
+                    &Self::combine_witnesses(accs, coeffs.as_slice()),
+                );
+
+                let res = Self::dec(srs, &acc).into_group() - acc.instance.E.E;
+                res.mul(vanishing_polynomial_at(k, t).inverse().unwrap())
+            })
+            .collect();
+
+        E::G1::normalize_batch(errors.as_slice())
+            .into_iter()
+            .map(|E| Acc3Error { E })
+            .collect()
+    }
+
+    /// returns the instance sum_i coeffs[i] * instances[i], the error term included
+    fn combine_instances(instances: &[Acc3Instance<E>], coeffs: &[E::ScalarField]) -> Acc3Instance<E> {
+        let commitments: Vec<E::G1Affine> = instances.iter().map(|instance| instance.C).collect();
+        let commitments_y: Vec<E::G1Affine> = instances.iter().map(|instance| instance.C_y).collect();
+        let T_terms: Vec<E::G1Affine> = instances.iter().map(|instance| instance.T).collect();
+        let errors: Vec<E::G1Affine> = instances.iter().map(|instance| instance.E.E).collect();
+        let x: Vec<&[E::ScalarField]> = instances.iter().map(|instance| instance.x.as_slice()).collect();
+        let y: Vec<&[E::ScalarField]> = instances.iter().map(|instance| instance.y.as_slice()).collect();
+        let z: Vec<&[E::ScalarField]> = instances.iter().map(|instance| instance.z.as_slice()).collect();
+
+        Acc3Instance {
+            C: E::G1::msm_unchecked(commitments.as_slice(), coeffs).into(),
+            C_y: E::G1::msm_unchecked(commitments_y.as_slice(), coeffs).into(),
+            T: E::G1::msm_unchecked(T_terms.as_slice(), coeffs).into(),
+            E: Acc3Error { E: E::G1::msm_unchecked(errors.as_slice(), coeffs).into() },
+            x: weighted_sum(x.as_slice(), coeffs),
+            y: weighted_sum(y.as_slice(), coeffs),
+            z: weighted_sum(z.as_slice(), coeffs),
+            output: instances.iter().zip(coeffs.iter()).map(|(instance, c)| instance.output * c).sum(),
+        }
+    }
+
+    /// returns the witness sum_i coeffs[i] * accs[i].witness
+    fn combine_witnesses(accs: &[Accumulator3<E>], coeffs: &[E::ScalarField]) -> Acc3Witness<E> {
+        let D_x: Vec<&[E::G1]> = accs.iter().map(|acc| acc.witness.D_x.as_slice()).collect();
+        let D_y: Vec<&[E::G1]> = accs.iter().map(|acc| acc.witness.D_y.as_slice()).collect();
+
+        Acc3Witness {
+            D_x: weighted_sum_of_points(D_x.as_slice(), coeffs),
+            D_y: weighted_sum_of_points(D_y.as_slice(), coeffs),
+            tree_x: EqTree::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.tree_x).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+            tree_y: EqTree::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.tree_y).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+            tree_z: EqTree::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.tree_z).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+            f_star: MultilinearPolynomial::weighted_sum(
+                accs.iter().map(|acc| &acc.witness.f_star).collect::<Vec<_>>().as_slice(),
+                coeffs,
+            ),
+        }
+    }
+}
+
 // deciding functions
 impl<E: Pairing> Accumulator3<E> {
     pub fn dec(srs: &Acc3SRS<E>, acc: &Accumulator3<E>) -> E::G1Affine {
@@ -551,6 +778,11 @@ impl<E: Pairing> Accumulator3<E> {
     }
 
     pub fn decide(srs: &Acc3SRS<E>, acc: &Accumulator3<E>) {
+        assert!(Self::is_satisfied(srs, acc), "the accumulator is not satisfied");
+    }
+
+    /// Same as `decide` but returns whether all the conditions hold instead of panicking
+    pub fn is_satisfied(srs: &Acc3SRS<E>, acc: &Accumulator3<E>) -> bool {
         let instance = &acc.instance;
         let witness = &acc.witness;
 
@@ -558,7 +790,9 @@ impl<E: Pairing> Accumulator3<E> {
         let pairing_lhs = E::multi_pairing(&witness.D_x, &srs.pc_srs.V_x);
         let pairing_rhs = E::pairing(instance.C, srs.pc_srs.v);
 
-        assert_eq!(pairing_lhs, pairing_rhs, "first condition fails");
+        if pairing_lhs != pairing_rhs {
+            return false;
+        }
 
         // second condition
         let ip_rhs = instance.T;
@@ -583,23 +817,106 @@ impl<E: Pairing> Accumulator3<E> {
             E::G1::msm_unchecked(combined_bases.as_slice(), combined_scalars.as_slice())
         };
 
-        assert_eq!(ip_rhs, ip_lhs.into(), "second condition fails");
+        if ip_rhs != ip_lhs.into() {
+            return false;
+        }
 
         // third condition
-        assert_eq!(
-            Self::dec(srs, acc),
-            acc.instance.E.E.into(),
-            "third condition fails"
-        );
+        if Self::dec(srs, acc) != acc.instance.E.E {
+            return false;
+        }
 
         // forth condition
         let pairing_lhs = E::multi_pairing(&witness.D_y, &srs.pc_srs.V_y);
         let pairing_rhs = E::pairing(instance.C_y, srs.pc_srs.v);
-        assert_eq!(pairing_lhs, pairing_rhs, "forth condition fails");
+        pairing_lhs == pairing_rhs
     }
 }
 
 
+impl<E: Pairing> AccumulationScheme<E> for Accumulator3<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
+{
+    const DIMENSION: u32 = 3;
+
+    type PCS = KZH3<E>;
+    type SRS = Acc3SRS<E>;
+    type Instance = Acc3Instance<E>;
+    type Witness = Acc3Witness<E>;
+    type Proof = Acc3Error<E>;
+
+    fn setup<R: RngCore>(pc_srs: KZH3SRS<E>, rng: &mut R) -> Acc3SRS<E> {
+        Accumulator3::setup(pc_srs, rng)
+    }
+
+    fn from_opening(
+        srs: &Acc3SRS<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH3Commitment<E>,
+        open: KZH3Opening<E>,
+    ) -> Accumulator3<E> {
+        let instance = Accumulator3::proof_to_accumulator_instance(srs, input, output, com, &open);
+        let witness = Accumulator3::proof_to_accumulator_witness(srs, open, input);
+
+        Accumulator3::new(&instance, &witness)
+    }
+
+    fn instance(&self) -> &Acc3Instance<E> {
+        &self.instance
+    }
+
+    fn witness(&self) -> &Acc3Witness<E> {
+        &self.witness
+    }
+
+    fn prove(
+        srs: &Acc3SRS<E>,
+        acc_1: &Accumulator3<E>,
+        acc_2: &Accumulator3<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Accumulator3<E>, Acc3Error<E>) {
+        let (instance, witness, proof) = Accumulator3::prove(srs, acc_1, acc_2, transcript);
+
+        (Accumulator3 { witness, instance }, proof)
+    }
+
+    fn verify(
+        srs: &Acc3SRS<E>,
+        instance_1: &Acc3Instance<E>,
+        instance_2: &Acc3Instance<E>,
+        proof: &Acc3Error<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Acc3Instance<E> {
+        Accumulator3::verify(srs, instance_1, instance_2, proof, transcript)
+    }
+
+    fn decide(srs: &Acc3SRS<E>, acc: &Accumulator3<E>) -> bool {
+        Accumulator3::is_satisfied(srs, acc)
+    }
+
+    fn has_valid_sizes(srs: &Acc3SRS<E>, acc: &Accumulator3<E>) -> bool {
+        let (instance, witness) = (&acc.instance, &acc.witness);
+        let (degree_x, degree_y, degree_z) = (srs.pc_srs.degree_x, srs.pc_srs.degree_y, srs.pc_srs.degree_z);
+
+        instance.x.len() == degree_x.log_2()
+            && instance.y.len() == degree_y.log_2()
+            && instance.z.len() == degree_z.log_2()
+            && witness.D_x.len() == degree_x
+            && witness.D_y.len() == degree_y
+            && witness.f_star.evaluation_over_boolean_hypercube.len() == degree_z
+            && witness.f_star.num_variables == degree_z.log_2()
+            && witness.tree_x.depth == degree_x.log_2()
+            && witness.tree_y.depth == degree_y.log_2()
+            && witness.tree_z.depth == degree_z.log_2()
+            && srs.k_x.len() == witness.tree_x.nodes.len()
+            && srs.k_y.len() == witness.tree_y.nodes.len()
+            && srs.k_z.len() == witness.tree_z.nodes.len()
+    }
+}
+
 // get fresh satisfying accumulator (zero error terms)
 impl<E: Pairing<ScalarField=F>, F: PrimeField + Absorb> Accumulator3<E> {
     // Helper function to create an accumulator from a random polynomial
@@ -621,9 +938,9 @@ impl<E: Pairing<ScalarField=F>, F: PrimeField + Absorb> Accumulator3<E> {
 
         let output = polynomial.evaluate(input.as_slice());
 
-        let commitment = KZH3::commit(&srs.pc_srs, &polynomial);
+        let (commitment, hint) = KZH3::commit(&srs.pc_srs, &polynomial);
 
-        let opening = KZH3::open(&srs.pc_srs, input.as_slice(), &commitment, &polynomial);
+        let opening = KZH3::open(&srs.pc_srs, input.as_slice(), &hint, &polynomial);
 
         // Convert proof to instance and witness
         let acc_instance = Accumulator3::proof_to_accumulator_instance(
@@ -636,7 +953,6 @@ impl<E: Pairing<ScalarField=F>, F: PrimeField + Absorb> Accumulator3<E> {
 
         let acc_witness = Accumulator3::proof_to_accumulator_witness(
             &srs,
-            commitment,
             opening,
             input.as_slice(),
         );
@@ -680,7 +996,7 @@ mod test {
     use crate::transcript::transcript::Transcript;
     use crate::utils::inner_product;
     use ark_ec::pairing::Pairing;
-    use ark_ec::CurveGroup;
+    use ark_ec::{AffineRepr, CurveGroup};
     use ark_ec::VariableBaseMSM;
     use ark_ff::AdditiveGroup;
     use rand::thread_rng;
@@ -775,4 +1091,107 @@ mod test {
 
         Accumulator3::decide(&acc_srs, &Accumulator3::new(&instance, &witness));
     }
+
+    #[test]
+    fn test_multi_fold() {
+        let pcs_srs: KZH3SRS<E> = KZH3::setup(6, &mut thread_rng());
+        let acc_srs = Accumulator3::setup(pcs_srs, &mut thread_rng());
+
+        for k in [2, 3, 4] {
+            let accs: Vec<Accumulator3<E>> = (0..k).map(|_| Accumulator3::rand(&acc_srs)).collect();
+            let instances: Vec<_> = accs.iter().map(|acc| acc.instance.clone()).collect();
+
+            let mut prover_transcript = Transcript::new(b"hi");
+            let mut verifier_transcript = prover_transcript.clone();
+
+            let (instance, witness, proof) = Accumulator3::prove_multi(&acc_srs, accs.as_slice(), &mut prover_transcript);
+            assert_eq!(proof.len(), k - 1);
+
+            let instance_expected = Accumulator3::verify_multi(&acc_srs, instances.as_slice(), proof.as_slice(), &mut verifier_transcript).unwrap();
+            assert_eq!(instance, instance_expected);
+
+            assert!(Accumulator3::is_satisfied(&acc_srs, &Accumulator3::new(&instance, &witness)));
+        }
+    }
+
+    #[test]
+    fn test_multi_fold_against_pairwise() {
+        let pcs_srs: KZH3SRS<E> = KZH3::setup(6, &mut thread_rng());
+        let acc_srs = Accumulator3::setup(pcs_srs, &mut thread_rng());
+
+        let accs: Vec<Accumulator3<E>> = (0..3).map(|_| Accumulator3::rand(&acc_srs)).collect();
+
+        // fold the accumulators one by one with the pairwise folding
+        let mut transcript = Transcript::new(b"hi");
+        let mut chained = accs[0].clone();
+        for acc in &accs[1..] {
+            let (instance, witness, _proof) = Accumulator3::prove(&acc_srs, &chained, acc, &mut transcript);
+            chained = Accumulator3::new(&instance, &witness);
+        }
+        assert!(Accumulator3::is_satisfied(&acc_srs, &chained));
+
+        // fold them in a single round
+        let (instance, witness, proof) = Accumulator3::prove_multi(&acc_srs, accs.as_slice(), &mut Transcript::new(b"hi"));
+        assert!(Accumulator3::is_satisfied(&acc_srs, &Accumulator3::new(&instance, &witness)));
+
+        // the instance folded by the verifier with a tampered error term doesn't match the witness
+        let instances: Vec<_> = accs.iter().map(|acc| acc.instance.clone()).collect();
+        let mut tampered_proof = proof.clone();
+        tampered_proof[1].E = (tampered_proof[1].E + <E as Pairing>::G1Affine::generator()).into_affine();
+        let instance = Accumulator3::verify_multi(&acc_srs, instances.as_slice(), tampered_proof.as_slice(), &mut Transcript::new(b"hi")).unwrap();
+        assert!(!Accumulator3::is_satisfied(&acc_srs, &Accumulator3::new(&instance, &witness)));
+
+        // the verifier rejects a proof without one error term per accumulator but one
+        assert_eq!(
+            Accumulator3::verify_multi(&acc_srs, instances.as_slice(), &proof[..1], &mut Transcript::new(b"hi")),
+            Err(FoldingError::InvalidErrorTermCount { expected: 2, found: 1 })
+        );
+        let mut extended_proof = proof.clone();
+        extended_proof.push(Acc3Error::zero());
+        assert_eq!(
+            Accumulator3::verify_multi(&acc_srs, instances.as_slice(), extended_proof.as_slice(), &mut Transcript::new(b"hi")),
+            Err(FoldingError::InvalidErrorTermCount { expected: 2, found: 3 })
+        );
+        assert_eq!(
+            Accumulator3::verify_multi(&acc_srs, &instances[..1], &[], &mut Transcript::new(b"hi")),
+            Err(FoldingError::TooFewAccumulators(1))
+        );
+
+        // for two accumulators, the quotient of the multi-folding is K(2) = -Q
+        let proof = Accumulator3::compute_error_term(&acc_srs, &accs[0], &accs[1]);
+        let proof_multi = Accumulator3::compute_multi_error_terms(&acc_srs, &accs[..2]);
+        assert_eq!(proof_multi.len(), 1);
+        assert_eq!(proof_multi[0].E, proof.E.neg());
+    }
+
+    #[test]
+    fn test_transparent_setup() {
+        let pcs_srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        let acc_srs = Accumulator3::setup_transparent(pcs_srs.clone(), b"kzh3 accumulator");
+
+        acc_srs.check_generators(b"kzh3 accumulator").unwrap();
+        acc_srs.trim(6).unwrap().check_generators(b"kzh3 accumulator").unwrap();
+        assert!(acc_srs.check_generators(b"another label").is_err());
+        assert!(Accumulator3::setup(pcs_srs, &mut thread_rng()).check_generators(b"kzh3 accumulator").is_err());
+
+        let acc_1 = Accumulator3::rand(&acc_srs);
+        let acc_2 = Accumulator3::rand(&acc_srs);
+
+        let (instance, witness, _proof) = Accumulator3::prove(&acc_srs, &acc_1, &acc_2, &mut Transcript::new(b"hi"));
+
+        Accumulator3::decide(&acc_srs, &Accumulator3::new(&instance, &witness));
+    }
+
+    #[test]
+    fn test_trimmed_srs() {
+        let pcs_srs: KZH3SRS<E> = KZH3::setup(9, &mut thread_rng());
+        let acc_srs = Accumulator3::setup(pcs_srs, &mut thread_rng()).trim(6).unwrap();
+
+        let acc_1 = Accumulator3::rand(&acc_srs);
+        let acc_2 = Accumulator3::rand(&acc_srs);
+
+        let (instance, witness, _proof) = Accumulator3::prove(&acc_srs, &acc_1, &acc_2, &mut Transcript::new(b"hi"));
+
+        Accumulator3::decide(&acc_srs, &Accumulator3::new(&instance, &witness));
+    }
 }
@@ -0,0 +1,1010 @@
+use crate::gadgets::non_native::util::convert_affine_to_scalars;
+use crate::kzh::errors::SRSError;
+use crate::kzh::kzh4::{KZH4Commitment, KZH4Opening, KZH4, KZH4SRS};
+use crate::kzh::KZH;
+use crate::kzh_fold::eq_tree::EqTree;
+use crate::kzh_fold::{AccumulationScheme, derive_generators, generate_random_elements, generic_linear_combination};
+use crate::math::Math;
+use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
+use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+use crate::transcript::transcript::Transcript;
+use crate::utils::inner_product;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_ec::{AffineRepr, VariableBaseMSM};
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use derivative::Derivative;
+use rand::{thread_rng, RngCore};
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Acc4Error<E: Pairing> {
+    pub E: E::G1Affine,
+}
+
+impl<E: Pairing> Acc4Error<E> {
+    /// Returns a new `Acc4Error` where all elements are `E::G1Affine::zero()`.
+    pub fn zero() -> Self {
+        Self {
+            E: E::G1Affine::zero(),
+        }
+    }
+
+    /// Returns the elements as a vector.
+    pub fn to_vec(&self) -> Vec<E::G1Affine> {
+        vec![self.E]
+    }
+}
+
+impl<E: Pairing> Acc4Error<E> {
+    /// Updates the error term based on the provided error instances, `pf`, and `beta`.
+    pub fn update(
+        error_instance_1: &Self,
+        error_instance_2: &Self,
+        pf: &Self,
+        beta: E::ScalarField,
+    ) -> Self {
+        let one_minus_beta = E::ScalarField::ONE - beta;
+        let beta_one_minus_beta = beta * one_minus_beta;
+
+        let E = error_instance_1.E.mul(one_minus_beta)
+            + error_instance_2.E.mul(beta)
+            + pf.E.mul(beta_one_minus_beta);
+
+        Self { E: E.into() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Acc4SRS<E: Pairing> {
+    // vector of size 2 * degree_x - 1
+    pub k_x: Vec<E::G1Affine>,
+    // vector of size 2 * degree_y - 1
+    pub k_y: Vec<E::G1Affine>,
+    // vector of size 2 * degree_z - 1
+    pub k_z: Vec<E::G1Affine>,
+    // vector of size 2 * degree_t - 1
+    pub k_t: Vec<E::G1Affine>,
+
+    pub k_prime: E::G1Affine,
+
+    pub pc_srs: KZH4SRS<E>,
+}
+
+impl<E: Pairing> Acc4SRS<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// Derives the accumulator SRS for a smaller maximum degree, see KZH4SRS::trim
+    pub fn trim(&self, maximum_degree: usize) -> Result<Acc4SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim(maximum_degree)?))
+    }
+
+    /// Derives the accumulator SRS for the given log degrees, see KZH4SRS::trim_with_degrees
+    pub fn trim_with_degrees(&self, degrees: (usize, usize, usize, usize)) -> Result<Acc4SRS<E>, SRSError> {
+        Ok(self.trim_bases(self.pc_srs.trim_with_degrees(degrees)?))
+    }
+
+    /// The bases k_x, k_y, k_z and k_t are independent so they are simply truncated to the sizes of the
+    /// trees of the trimmed pc_srs
+    fn trim_bases(&self, pc_srs: KZH4SRS<E>) -> Acc4SRS<E> {
+        Acc4SRS {
+            k_x: self.k_x[..2 * pc_srs.degree_x - 1].to_vec(),
+            k_y: self.k_y[..2 * pc_srs.degree_y - 1].to_vec(),
+            k_z: self.k_z[..2 * pc_srs.degree_z - 1].to_vec(),
+            k_t: self.k_t[..2 * pc_srs.degree_t - 1].to_vec(),
+            k_prime: self.k_prime,
+            pc_srs,
+        }
+    }
+
+    /// Checks that k_x, k_y, k_z, k_t and k_prime are the generators derived from `label` by
+    /// Accumulator4::setup_transparent, so that nobody knows discrete-log relations among them
+    pub fn check_generators(&self, label: &[u8]) -> Result<(), SRSError> {
+        if self.k_x.len() != 2 * self.pc_srs.degree_x - 1
+            || self.k_x != derive_generators::<E>(label, b"k_x", self.k_x.len())
+            || self.k_y.len() != 2 * self.pc_srs.degree_y - 1
+            || self.k_y != derive_generators::<E>(label, b"k_y", self.k_y.len())
+            || self.k_z.len() != 2 * self.pc_srs.degree_z - 1
+            || self.k_z != derive_generators::<E>(label, b"k_z", self.k_z.len())
+            || self.k_t.len() != 2 * self.pc_srs.degree_t - 1
+            || self.k_t != derive_generators::<E>(label, b"k_t", self.k_t.len())
+            || self.k_prime != derive_generators::<E>(label, b"k_prime", 1)[0]
+        {
+            return Err(SRSError::InvalidGenerators);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Acc4Instance<E: Pairing> {
+    pub C: E::G1Affine,
+    // C_y = <D_x, eq(x)>, the commitment to f(x, ., ., .)
+    pub C_y: E::G1Affine,
+    // C_z = <D_y, eq(y)>, the commitment to f(x, y, ., .)
+    pub C_z: E::G1Affine,
+    pub T: E::G1Affine,
+    pub E: Acc4Error<E>,
+
+    // vector of length log2(degree_x)
+    pub x: Vec<E::ScalarField>,
+    // vector of length log2(degree_y)
+    pub y: Vec<E::ScalarField>,
+    // vector of length log2(degree_z)
+    pub z: Vec<E::ScalarField>,
+    // vector of length log2(degree_t)
+    pub t: Vec<E::ScalarField>,
+    // result of polynomial evaluation
+    pub output: E::ScalarField,
+}
+
+impl<E: Pairing> Acc4Instance<E>
+where
+    E::ScalarField: PrimeField,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+{
+    /// returns a vector of E::Scalar that can be used to Poseidon hash
+    /// the tricky part is the affine points which are transformed via convert_affine_to_scalars
+    /// which basically converts each coordinate from base field into scalar field
+    pub fn to_sponge_field_elements(&self) -> Vec<E::ScalarField> {
+        let mut dest = Vec::new();
+
+        let (c_x, c_y) = convert_affine_to_scalars::<E>(self.C);
+        let (c_y_x, c_y_y) = convert_affine_to_scalars::<E>(self.C_y);
+        let (c_z_x, c_z_y) = convert_affine_to_scalars::<E>(self.C_z);
+        let (t_x, t_y) = convert_affine_to_scalars::<E>(self.T);
+
+        // Extend the destination vector with the computed values
+        dest.extend(vec![c_x, c_y, c_y_x, c_y_y, c_z_x, c_z_y, t_x, t_y]);
+
+        for E in self.E.to_vec() {
+            let (e_x, e_y) = convert_affine_to_scalars::<E>(E);
+            dest.extend(vec![e_x, e_y]);
+        }
+
+        // Extend with other scalar fields
+        dest.extend(self.x.clone());
+        dest.extend(self.y.clone());
+        dest.extend(self.z.clone());
+        dest.extend(self.t.clone());
+        dest.push(self.output.clone());
+
+        dest
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Derivative)]
+pub struct Acc4Witness<E: Pairing> {
+    pub D_x: Vec<E::G1>,
+    pub D_y: Vec<E::G1>,
+    pub D_z: Vec<E::G1>,
+    pub tree_x: EqTree<E::ScalarField>,
+    pub tree_y: EqTree<E::ScalarField>,
+    pub tree_z: EqTree<E::ScalarField>,
+    pub tree_t: EqTree<E::ScalarField>,
+    pub f_star: MultilinearPolynomial<E::ScalarField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Accumulator4<E: Pairing> {
+    pub witness: Acc4Witness<E>,
+    pub instance: Acc4Instance<E>,
+}
+
+impl<E: Pairing> Accumulator4<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
+{
+    pub fn setup<R: RngCore>(pc_srs: KZH4SRS<E>, rng: &mut R) -> Acc4SRS<E> {
+        Acc4SRS {
+            pc_srs: pc_srs.clone(),
+            k_x: generate_random_elements::<E, R>(2 * pc_srs.degree_x - 1, rng),
+            k_y: generate_random_elements::<E, R>(2 * pc_srs.degree_y - 1, rng),
+            k_z: generate_random_elements::<E, R>(2 * pc_srs.degree_z - 1, rng),
+            k_t: generate_random_elements::<E, R>(2 * pc_srs.degree_t - 1, rng),
+            k_prime: E::G1Affine::rand(rng),
+        }
+    }
+
+    /// Same as `setup` but k_x, k_y, k_z, k_t and k_prime are derived from the domain-separation `label` instead
+    /// of sampled by the caller, anyone can then check them with Acc4SRS::check_generators
+    pub fn setup_transparent(pc_srs: KZH4SRS<E>, label: &[u8]) -> Acc4SRS<E> {
+        Acc4SRS {
+            pc_srs: pc_srs.clone(),
+            k_x: derive_generators::<E>(label, b"k_x", 2 * pc_srs.degree_x - 1),
+            k_y: derive_generators::<E>(label, b"k_y", 2 * pc_srs.degree_y - 1),
+            k_z: derive_generators::<E>(label, b"k_z", 2 * pc_srs.degree_z - 1),
+            k_t: derive_generators::<E>(label, b"k_t", 2 * pc_srs.degree_t - 1),
+            k_prime: derive_generators::<E>(label, b"k_prime", 1)[0],
+        }
+    }
+
+    pub fn new(instance: &Acc4Instance<E>, witness: &Acc4Witness<E>) -> Accumulator4<E> {
+        Accumulator4 {
+            witness: witness.clone(),
+            instance: instance.clone(),
+        }
+    }
+
+    /// the fiat-shamir challenge is computed as part the transcript operations via hashing two accumulator instances and the proof
+    pub fn compute_fiat_shamir_challenge(
+        transcript: &mut Transcript<E::ScalarField>,
+        instance_1: &Acc4Instance<E>,
+        instance_2: &Acc4Instance<E>,
+        proof: &Acc4Error<E>,
+    ) -> E::ScalarField {
+        // add the instances to the transcript
+        transcript.append_scalars(b"instance 1", instance_1.to_sponge_field_elements().as_slice());
+        transcript.append_scalars(b"instance 2", instance_2.to_sponge_field_elements().as_slice());
+
+        // convert the proof into scalar field elements and add to the transcript
+        for E in proof.to_vec() {
+            let (p1, p2) = convert_affine_to_scalars::<E>(E);
+            transcript.append_scalars(b"proof", &[p1, p2]);
+        }
+
+        // return the challenge
+        transcript.challenge_scalar(b"challenge scalar")
+    }
+}
+
+// impl function to convert proof into accumulator
+impl<E: Pairing> Accumulator4<E> {
+    /// Unlike KZH3, the KZH4 opening does not contain the intermediate commitments, C_y and C_z
+    /// are recomputed from D_x and D_y the same way KZH4::verify does
+    pub fn proof_to_accumulator_instance(
+        srs: &Acc4SRS<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH4Commitment<E>,
+        open: &KZH4Opening<E>,
+    ) -> Acc4Instance<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        let split_input = KZH4::split_input(&srs.pc_srs, input, E::ScalarField::ZERO);
+
+        let T = {
+            let tree_x = EqTree::new(split_input[0].as_slice());
+            let tree_y = EqTree::new(split_input[1].as_slice());
+            let tree_z = EqTree::new(split_input[2].as_slice());
+            let tree_t = EqTree::new(split_input[3].as_slice());
+
+            let mut T: E::G1 = E::G1::ZERO;
+            T = T.add(E::G1::msm_unchecked(srs.k_x.as_slice(), tree_x.nodes.as_slice()));
+            T = T.add(E::G1::msm_unchecked(srs.k_y.as_slice(), tree_y.nodes.as_slice()));
+            T = T.add(E::G1::msm_unchecked(srs.k_z.as_slice(), tree_z.nodes.as_slice()));
+            T = T.add(E::G1::msm_unchecked(srs.k_t.as_slice(), tree_t.nodes.as_slice()));
+
+            assert_eq!(srs.k_x.len(), tree_x.nodes.len(), "invalid size of vector x");
+            assert_eq!(srs.k_y.len(), tree_y.nodes.len(), "invalid size of vector y");
+            assert_eq!(srs.k_z.len(), tree_z.nodes.len(), "invalid size of vector z");
+            assert_eq!(srs.k_t.len(), tree_t.nodes.len(), "invalid size of vector t");
+
+            T.into()
+        };
+
+        let C_y = E::G1::msm_unchecked(
+            E::G1::normalize_batch(open.D_x.as_slice()).as_slice(),
+            EqPolynomial::new(split_input[0].clone()).evals().as_slice(),
+        );
+
+        let C_z = E::G1::msm_unchecked(
+            E::G1::normalize_batch(open.D_y.as_slice()).as_slice(),
+            EqPolynomial::new(split_input[1].clone()).evals().as_slice(),
+        );
+
+        Acc4Instance {
+            C: com.C,
+            C_y: C_y.into(),
+            C_z: C_z.into(),
+            T,
+            E: Acc4Error::zero(),
+            x: split_input[0].clone(),
+            y: split_input[1].clone(),
+            z: split_input[2].clone(),
+            t: split_input[3].clone(),
+            output: *output,
+        }
+    }
+
+    pub fn proof_to_accumulator_witness(
+        srs: &Acc4SRS<E>,
+        proof: KZH4Opening<E>,
+        input: &[E::ScalarField],
+    ) -> Acc4Witness<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        let split_input = KZH4::split_input(&srs.pc_srs, input, E::ScalarField::ZERO);
+
+        Acc4Witness {
+            D_x: proof.D_x,
+            D_y: proof.D_y,
+            D_z: proof.D_z,
+            tree_x: EqTree::new(split_input[0].as_slice()),
+            tree_y: EqTree::new(split_input[1].as_slice()),
+            tree_z: EqTree::new(split_input[2].as_slice()),
+            tree_t: EqTree::new(split_input[3].as_slice()),
+            f_star: proof.f_star,
+        }
+    }
+}
+
+// verify and prove function
+impl<E: Pairing> Accumulator4<E> {
+    pub fn prove(
+        srs: &Acc4SRS<E>,
+        acc_1: &Accumulator4<E>,
+        acc_2: &Accumulator4<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Acc4Instance<E>, Acc4Witness<E>, Acc4Error<E>)
+    where
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        // unwrap the instances and witnesses
+        let instance_1 = &acc_1.instance;
+        let instance_2 = &acc_2.instance;
+        let witness_1 = &acc_1.witness;
+        let witness_2 = &acc_2.witness;
+
+        // compute the quotient variable Q
+        let proof: Acc4Error<E> = Self::compute_error_term(srs, acc_1, acc_2);
+
+        // the transcript is cloned because is used twice, once to compute the fiat_shamir challenge beta directly
+        // once to call Self::verify() to get the new accumulated instance, despite that they generate the same randomness
+        // and one can do fewer hashes by computing accumulated instance directly, it's for the sake of cleaner code.
+        let mut transcript_clone = transcript.clone();
+
+        // get challenge beta
+        let beta = Accumulator4::compute_fiat_shamir_challenge(transcript, instance_1, instance_2, &proof);
+        let one_minus_beta: E::ScalarField = E::ScalarField::ONE - beta;
+
+        // get the accumulated new_instance
+        let new_instance = Self::verify(srs, instance_1, instance_2, &proof, &mut transcript_clone);
+
+        // get the accumulated witness
+        let new_witness = Acc4Witness {
+            D_x: generic_linear_combination(
+                &witness_1.D_x,
+                &witness_2.D_x,
+                |d_1, d_2| d_1.mul(one_minus_beta).add(d_2.mul(beta)),
+            ),
+            D_y: generic_linear_combination(
+                &witness_1.D_y,
+                &witness_2.D_y,
+                |d_1, d_2| d_1.mul(one_minus_beta).add(d_2.mul(beta)),
+            ),
+            D_z: generic_linear_combination(
+                &witness_1.D_z,
+                &witness_2.D_z,
+                |d_1, d_2| d_1.mul(one_minus_beta).add(d_2.mul(beta)),
+            ),
+            f_star: MultilinearPolynomial::linear_combination(
+                &witness_1.f_star,
+                &witness_2.f_star,
+                |a, b| a * one_minus_beta + b * beta,
+            ),
+            tree_x: EqTree::linear_combination(
+                &witness_1.tree_x,
+                &witness_2.tree_x,
+                |a, b| a * one_minus_beta + b * beta,
+            ),
+            tree_y: EqTree::linear_combination(
+                &witness_1.tree_y,
+                &witness_2.tree_y,
+                |a, b| a * one_minus_beta + b * beta,
+            ),
+            tree_z: EqTree::linear_combination(
+                &witness_1.tree_z,
+                &witness_2.tree_z,
+                |a, b| a * one_minus_beta + b * beta,
+            ),
+            tree_t: EqTree::linear_combination(
+                &witness_1.tree_t,
+                &witness_2.tree_t,
+                |a, b| a * one_minus_beta + b * beta,
+            ),
+        };
+
+        (new_instance, new_witness, proof)
+    }
+
+    pub fn verify(
+        _srs: &Acc4SRS<E>,
+        instance_1: &Acc4Instance<E>,
+        instance_2: &Acc4Instance<E>,
+        proof: &Acc4Error<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Acc4Instance<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        let beta = Accumulator4::compute_fiat_shamir_challenge(transcript, instance_1, instance_2, &proof);
+        let one_minus_beta: E::ScalarField = E::ScalarField::ONE - beta;
+
+        let new_error_term = Acc4Error::update(
+            &instance_1.E,
+            &instance_2.E,
+            &proof,
+            beta,
+        );
+
+        Acc4Instance {
+            C: (instance_1.C.mul(one_minus_beta) + instance_2.C.mul(beta)).into(),
+            C_y: (instance_1.C_y.mul(one_minus_beta) + instance_2.C_y.mul(beta)).into(),
+            C_z: (instance_1.C_z.mul(one_minus_beta) + instance_2.C_z.mul(beta)).into(),
+            T: (instance_1.T.mul(one_minus_beta) + instance_2.T.mul(beta)).into(),
+            x: generic_linear_combination(
+                &instance_1.x,
+                &instance_2.x,
+                |e1, e2| e1 * one_minus_beta + e2 * beta,
+            ),
+            y: generic_linear_combination(
+                &instance_1.y,
+                &instance_2.y,
+                |e1, e2| e1 * one_minus_beta + e2 * beta,
+            ),
+            z: generic_linear_combination(
+                &instance_1.z,
+                &instance_2.z,
+                |e1, e2| e1 * one_minus_beta + e2 * beta,
+            ),
+            t: generic_linear_combination(
+                &instance_1.t,
+                &instance_2.t,
+                |e1, e2| e1 * one_minus_beta + e2 * beta,
+            ),
+            E: new_error_term,
+            output: instance_1.output * one_minus_beta + instance_2.output * beta,
+        }
+    }
+
+    pub fn compute_error_term(
+        srs: &Acc4SRS<E>,
+        acc_1: &Accumulator4<E>,
+        acc_2: &Accumulator4<E>,
+    ) -> Acc4Error<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        // unwrap the instances/witnesses
+        let instance_1 = &acc_1.instance;
+        let instance_2 = &acc_2.instance;
+        let witness_1 = &acc_1.witness;
+        let witness_2 = &acc_2.witness;
+
+        assert_eq!(witness_1.f_star.num_variables, witness_2.f_star.num_variables);
+        assert_eq!(witness_1.tree_x.depth, witness_2.tree_x.depth);
+        assert_eq!(witness_1.tree_y.depth, witness_2.tree_y.depth);
+        assert_eq!(witness_1.tree_z.depth, witness_2.tree_z.depth);
+        assert_eq!(witness_1.tree_t.depth, witness_2.tree_t.depth);
+        assert_eq!(witness_1.D_x.len(), witness_2.D_x.len());
+
+        // value of 2 in the field
+        let two = E::ScalarField::from(2u128);
+
+        let witness = Acc4Witness {
+            D_x: generic_linear_combination(
+                &witness_2.D_x,
+                &witness_1.D_x,
+                |d2, d1| d2.mul(two).sub(d1),
+            ),
+            D_y: generic_linear_combination(
+                &witness_2.D_y,
+                &witness_1.D_y,
+                |d2, d1| d2.mul(two).sub(d1),
+            ),
+            D_z: generic_linear_combination(
+                &witness_2.D_z,
+                &witness_1.D_z,
+                |d2, d1| d2.mul(two).sub(d1),
+            ),
+            f_star: MultilinearPolynomial::linear_combination(
+                &witness_2.f_star,
+                &witness_1.f_star,
+                |e2, e1| e2 * two - e1,
+            ),
+            tree_x: EqTree::linear_combination(
+                &witness_2.tree_x,
+                &witness_1.tree_x,
+                |w2, w1| w2 * two - w1,
+            ),
+            tree_y: EqTree::linear_combination(
+                &witness_2.tree_y,
+                &witness_1.tree_y,
+                |w2, w1| w2 * two - w1,
+            ),
+            tree_z: EqTree::linear_combination(
+                &witness_2.tree_z,
+                &witness_1.tree_z,
+                |w2, w1| w2 * two - w1,
+            ),
+            tree_t: EqTree::linear_combination(
+                &witness_2.tree_t,
+                &witness_1.tree_t,
+                |w2, w1| w2 * two - w1,
+            ),
+        };
+
+        let instance = Acc4Instance {
+            C_y: (instance_2.C_y + instance_2.C_y - instance_1.C_y).into(),
+            C_z: (instance_2.C_z + instance_2.C_z - instance_1.C_z).into(),
+            // not used by helper function, so we simply pass them as zero or any other random element
+            C: E::G1Affine::zero(),
+            T: E::G1Affine::zero(),
+            E: Acc4Error::zero(),
+            // used by the helper function
+            x: generic_linear_combination(
+                &instance_2.x,
+                &instance_1.x,
+                |e2, e1| e2 * two - e1,
+            ),
+            y: generic_linear_combination(
+                &instance_2.y,
+                &instance_1.y,
+                |e2, e1| e2 * two - e1,
+            ),
+            z: generic_linear_combination(
+                &instance_2.z,
+                &instance_1.z,
+                |e2, e1| e2 * two - e1,
+            ),
+            t: generic_linear_combination(
+                &instance_2.t,
+                &instance_1.t,
+                |e2, e1| e2 * two - e1,
+            ),
+            output: two * instance_2.output - instance_1.output,
+        };
+
+        let acc = Accumulator4::new(&instance, &witness);
+
+        let E = {
+            let res = Self::dec(&srs, &acc)
+                + &instance_1.E.E
+                - &instance_2.E.E
+                - &instance_2.E.E;
+
+            // -1/2 in the scalar field
+            let minus_one_over_two: E::ScalarField = two.neg().inverse().unwrap();
+            res.mul(minus_one_over_two).into()
+        };
+
+        Acc4Error {
+            E
+        }
+    }
+}
+
+// deciding functions
+impl<E: Pairing> Accumulator4<E> {
+    pub fn dec(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        // Extract required components
+        let error_tree_x = acc.witness.tree_x.difference(acc.instance.x.as_slice());
+        let error_tree_y = acc.witness.tree_y.difference(acc.instance.y.as_slice());
+        let error_tree_z = acc.witness.tree_z.difference(acc.instance.z.as_slice());
+        let error_tree_t = acc.witness.tree_t.difference(acc.instance.t.as_slice());
+
+        // Compute e_prime for dec_2
+        let e_prime: E::ScalarField = inner_product(
+            &acc.witness.f_star.evaluation_over_boolean_hypercube,
+            &acc.witness.tree_t.get_leaves(),
+        ) - acc.instance.output;
+
+        // Prepare scalars and bases for the combined MSM
+        let mut scalars = Vec::new();
+        let mut bases = Vec::new();
+
+        // Add dec_1 components
+        scalars.extend_from_slice(error_tree_x.nodes.as_slice());
+        bases.extend_from_slice(srs.k_x.as_slice());
+
+        scalars.extend_from_slice(error_tree_y.nodes.as_slice());
+        bases.extend_from_slice(srs.k_y.as_slice());
+
+        scalars.extend_from_slice(error_tree_z.nodes.as_slice());
+        bases.extend_from_slice(srs.k_z.as_slice());
+
+        scalars.extend_from_slice(error_tree_t.nodes.as_slice());
+        bases.extend_from_slice(srs.k_t.as_slice());
+
+        // Add dec_2 component
+        scalars.push(e_prime);
+        bases.push(srs.k_prime);
+
+        // Add dec_3 components
+        scalars.extend_from_slice(
+            acc.witness.f_star.evaluation_over_boolean_hypercube.as_slice(),
+        );
+        bases.extend_from_slice(srs.pc_srs.H_t.as_slice());
+
+        scalars.extend(
+            acc.witness
+                .tree_z
+                .get_leaves()
+                .iter()
+                .map(|g| g.neg()),
+        );
+        bases.extend(E::G1::normalize_batch(acc.witness.D_z.as_slice()));
+
+        // Add dec_4 components
+        scalars.extend_from_slice(acc.witness.tree_y.get_leaves());
+        bases.extend(E::G1::normalize_batch(acc.witness.D_y.as_slice()));
+
+        // Add dec_5 components
+        scalars.extend_from_slice(acc.witness.tree_x.get_leaves());
+        bases.extend(E::G1::normalize_batch(acc.witness.D_x.as_slice()));
+
+        // Perform the combined MSM
+        let msm_result = E::G1::msm_unchecked(bases.as_slice(), scalars.as_slice());
+
+        // Add final adjustments from dec_4 and dec_5
+        let final_result = msm_result - acc.instance.C_z - acc.instance.C_y;
+
+        // Return as affine point
+        final_result.into()
+    }
+
+    pub fn decide(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) {
+        assert!(Self::is_satisfied(srs, acc), "the accumulator is not satisfied");
+    }
+
+    /// Same as `decide` but returns whether all the conditions hold instead of panicking
+    pub fn is_satisfied(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> bool {
+        let instance = &acc.instance;
+        let witness = &acc.witness;
+
+        // first condition
+        let pairing_lhs = E::multi_pairing(&witness.D_x, &srs.pc_srs.V_x);
+        let pairing_rhs = E::pairing(instance.C, srs.pc_srs.v);
+
+        if pairing_lhs != pairing_rhs {
+            return false;
+        }
+
+        // second condition
+        let ip_rhs = instance.T;
+        let ip_lhs = {
+            // Concatenate bases and scalars
+            let mut combined_bases = Vec::with_capacity(
+                srs.k_x.len() + srs.k_y.len() + srs.k_z.len() + srs.k_t.len()
+            );
+            let mut combined_scalars = Vec::with_capacity(
+                witness.tree_x.nodes.len() + witness.tree_y.nodes.len() + witness.tree_z.nodes.len() + witness.tree_t.nodes.len(),
+            );
+
+            combined_bases.extend_from_slice(srs.k_x.as_slice());
+            combined_bases.extend_from_slice(srs.k_y.as_slice());
+            combined_bases.extend_from_slice(srs.k_z.as_slice());
+            combined_bases.extend_from_slice(srs.k_t.as_slice());
+
+            combined_scalars.extend_from_slice(witness.tree_x.nodes.as_slice());
+            combined_scalars.extend_from_slice(witness.tree_y.nodes.as_slice());
+            combined_scalars.extend_from_slice(witness.tree_z.nodes.as_slice());
+            combined_scalars.extend_from_slice(witness.tree_t.nodes.as_slice());
+
+            // Perform a single MSM
+            E::G1::msm_unchecked(combined_bases.as_slice(), combined_scalars.as_slice())
+        };
+
+        if ip_rhs != ip_lhs.into() {
+            return false;
+        }
+
+        // third condition
+        if Self::dec(srs, acc) != acc.instance.E.E {
+            return false;
+        }
+
+        // forth condition
+        let pairing_lhs = E::multi_pairing(&witness.D_y, &srs.pc_srs.V_y);
+        let pairing_rhs = E::pairing(instance.C_y, srs.pc_srs.v);
+        if pairing_lhs != pairing_rhs {
+            return false;
+        }
+
+        // fifth condition
+        let pairing_lhs = E::multi_pairing(&witness.D_z, &srs.pc_srs.V_z);
+        let pairing_rhs = E::pairing(instance.C_z, srs.pc_srs.v);
+        pairing_lhs == pairing_rhs
+    }
+}
+
+
+impl<E: Pairing> AccumulationScheme<E> for Accumulator4<E>
+where
+    <E as Pairing>::ScalarField: Absorb,
+    <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
+{
+    const DIMENSION: u32 = 4;
+
+    type PCS = KZH4<E>;
+    type SRS = Acc4SRS<E>;
+    type Instance = Acc4Instance<E>;
+    type Witness = Acc4Witness<E>;
+    type Proof = Acc4Error<E>;
+
+    fn setup<R: RngCore>(pc_srs: KZH4SRS<E>, rng: &mut R) -> Acc4SRS<E> {
+        Accumulator4::setup(pc_srs, rng)
+    }
+
+    fn from_opening(
+        srs: &Acc4SRS<E>,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &KZH4Commitment<E>,
+        open: KZH4Opening<E>,
+    ) -> Accumulator4<E> {
+        let instance = Accumulator4::proof_to_accumulator_instance(srs, input, output, com, &open);
+        let witness = Accumulator4::proof_to_accumulator_witness(srs, open, input);
+
+        Accumulator4::new(&instance, &witness)
+    }
+
+    fn instance(&self) -> &Acc4Instance<E> {
+        &self.instance
+    }
+
+    fn witness(&self) -> &Acc4Witness<E> {
+        &self.witness
+    }
+
+    fn prove(
+        srs: &Acc4SRS<E>,
+        acc_1: &Accumulator4<E>,
+        acc_2: &Accumulator4<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Accumulator4<E>, Acc4Error<E>) {
+        let (instance, witness, proof) = Accumulator4::prove(srs, acc_1, acc_2, transcript);
+
+        (Accumulator4 { witness, instance }, proof)
+    }
+
+    fn verify(
+        srs: &Acc4SRS<E>,
+        instance_1: &Acc4Instance<E>,
+        instance_2: &Acc4Instance<E>,
+        proof: &Acc4Error<E>,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Acc4Instance<E> {
+        Accumulator4::verify(srs, instance_1, instance_2, proof, transcript)
+    }
+
+    fn decide(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> bool {
+        Accumulator4::is_satisfied(srs, acc)
+    }
+
+    fn has_valid_sizes(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> bool {
+        let (instance, witness) = (&acc.instance, &acc.witness);
+        let pc_srs = &srs.pc_srs;
+
+        instance.x.len() == pc_srs.degree_x.log_2()
+            && instance.y.len() == pc_srs.degree_y.log_2()
+            && instance.z.len() == pc_srs.degree_z.log_2()
+            && instance.t.len() == pc_srs.degree_t.log_2()
+            && witness.D_x.len() == pc_srs.degree_x
+            && witness.D_y.len() == pc_srs.degree_y
+            && witness.D_z.len() == pc_srs.degree_z
+            && witness.f_star.evaluation_over_boolean_hypercube.len() == pc_srs.degree_t
+            && witness.f_star.num_variables == pc_srs.degree_t.log_2()
+            && witness.tree_x.depth == pc_srs.degree_x.log_2()
+            && witness.tree_y.depth == pc_srs.degree_y.log_2()
+            && witness.tree_z.depth == pc_srs.degree_z.log_2()
+            && witness.tree_t.depth == pc_srs.degree_t.log_2()
+            && srs.k_x.len() == witness.tree_x.nodes.len()
+            && srs.k_y.len() == witness.tree_y.nodes.len()
+            && srs.k_z.len() == witness.tree_z.nodes.len()
+            && srs.k_t.len() == witness.tree_t.nodes.len()
+    }
+}
+
+// get fresh satisfying accumulator (zero error terms)
+impl<E: Pairing<ScalarField=F>, F: PrimeField + Absorb> Accumulator4<E> {
+    // Helper function to create an accumulator from a random polynomial
+    fn rand_fresh_accumulator(
+        srs: &Acc4SRS<E>,
+    ) -> Accumulator4<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        let num_vars = srs.pc_srs.degree_x.log_2()
+            + srs.pc_srs.degree_y.log_2()
+            + srs.pc_srs.degree_z.log_2()
+            + srs.pc_srs.degree_t.log_2();
+
+        let input: Vec<F> = (0..num_vars)
+            .map(|_| F::rand(&mut thread_rng()))
+            .collect();
+
+        let polynomial = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+
+        let output = polynomial.evaluate(input.as_slice());
+
+        let (commitment, hint) = KZH4::commit(&srs.pc_srs, &polynomial);
+
+        let opening = KZH4::open(&srs.pc_srs, input.as_slice(), &hint, &polynomial);
+
+        // Convert proof to instance and witness
+        let acc_instance = Accumulator4::proof_to_accumulator_instance(
+            &srs,
+            input.as_slice(),
+            &output,
+            &commitment,
+            &opening,
+        );
+
+        let acc_witness = Accumulator4::proof_to_accumulator_witness(
+            &srs,
+            opening,
+            input.as_slice(),
+        );
+
+        Accumulator4::new(&acc_instance, &acc_witness)
+    }
+
+    pub fn rand(srs: &Acc4SRS<E>) -> Accumulator4<E>
+    where
+        <E as Pairing>::ScalarField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb,
+        <<E as Pairing>::G1Affine as AffineRepr>::BaseField: PrimeField,
+    {
+        // Create two accumulators
+        let acc1 = Accumulator4::rand_fresh_accumulator(srs);
+        let acc2 = Accumulator4::rand_fresh_accumulator(srs);
+
+        // Prove and decide final accumulator
+        let (instance, witness, _proof) = Accumulator4::prove(
+            srs,
+            &acc1,
+            &acc2,
+            &mut Transcript::new(b"hi"),
+        );
+
+        let final_acc = Accumulator4::new(&instance, &witness);
+
+        Accumulator4::decide(srs, &final_acc);
+
+        final_acc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constant_for_curves::E;
+    use crate::kzh::kzh4::{KZH4, KZH4SRS};
+    use crate::kzh::KZH;
+    use crate::kzh_fold::kzh4_fold::{Acc4SRS, Accumulator4};
+    use crate::transcript::transcript::Transcript;
+    use crate::utils::inner_product;
+    use ark_ec::pairing::Pairing;
+    use ark_ec::CurveGroup;
+    use ark_ec::VariableBaseMSM;
+    use ark_ff::AdditiveGroup;
+    use rand::thread_rng;
+    use std::ops::{Add, Mul, Neg};
+
+    fn dec_1<E: Pairing>(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        let error_tree_x = acc.witness.tree_x.difference(acc.instance.x.as_slice());
+        let error_tree_y = acc.witness.tree_y.difference(acc.instance.y.as_slice());
+        let error_tree_z = acc.witness.tree_z.difference(acc.instance.z.as_slice());
+        let error_tree_t = acc.witness.tree_t.difference(acc.instance.t.as_slice());
+
+        let mut res: E::G1 = E::G1::ZERO;
+        res = res.add(E::G1::msm_unchecked(srs.k_x.as_slice(), error_tree_x.nodes.as_slice()));
+        res = res.add(E::G1::msm_unchecked(srs.k_y.as_slice(), error_tree_y.nodes.as_slice()));
+        res = res.add(E::G1::msm_unchecked(srs.k_z.as_slice(), error_tree_z.nodes.as_slice()));
+        res = res.add(E::G1::msm_unchecked(srs.k_t.as_slice(), error_tree_t.nodes.as_slice()));
+
+        res.into()
+    }
+
+    fn dec_2<E: Pairing>(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        let e_prime: E::ScalarField = inner_product(
+            &acc.witness.f_star.evaluation_over_boolean_hypercube,
+            &acc.witness.tree_t.get_leaves(),
+        ) - acc.instance.output;
+
+        srs.k_prime.mul(e_prime).into()
+    }
+
+    fn dec_3<E: Pairing>(srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        let rhs = E::G1::msm_unchecked(
+            srs.pc_srs.H_t.as_slice(),
+            acc.witness.f_star.evaluation_over_boolean_hypercube.as_slice(),
+        );
+
+        let lhs = E::G1::msm_unchecked(
+            E::G1::normalize_batch(acc.witness.D_z.as_slice()).as_slice(),
+            acc.witness.tree_z.get_leaves(),
+        );
+
+        rhs.add(lhs.neg()).into()
+    }
+
+    fn dec_4<E: Pairing>(_srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        let lhs = E::G1::msm_unchecked(
+            E::G1::normalize_batch(acc.witness.D_y.as_slice()).as_slice(),
+            acc.witness.tree_y.get_leaves(),
+        );
+
+        acc.instance.C_z.add(lhs.neg()).neg().into()
+    }
+
+    fn dec_5<E: Pairing>(_srs: &Acc4SRS<E>, acc: &Accumulator4<E>) -> E::G1Affine {
+        let lhs = E::G1::msm_unchecked(
+            E::G1::normalize_batch(acc.witness.D_x.as_slice()).as_slice(),
+            acc.witness.tree_x.get_leaves(),
+        );
+
+        acc.instance.C_y.add(lhs.neg()).neg().into()
+    }
+
+    #[test]
+    fn test_dec() {
+        let pcs_srs: KZH4SRS<E> = KZH4::setup(8, &mut thread_rng());
+        let acc_srs = Accumulator4::setup(pcs_srs, &mut thread_rng());
+
+        let acc = Accumulator4::rand(&acc_srs);
+
+        assert_eq!(
+            Accumulator4::dec(&acc_srs, &acc),
+            (dec_1(&acc_srs, &acc)
+                + dec_2(&acc_srs, &acc)
+                + dec_3(&acc_srs, &acc)
+                + dec_4(&acc_srs, &acc)
+                + dec_5(&acc_srs, &acc)).into_affine(),
+        )
+    }
+
+    #[test]
+    fn kzh4_fold_test() {
+        let pcs_srs: KZH4SRS<E> = KZH4::setup(9, &mut thread_rng());
+        let acc_srs = Accumulator4::setup(pcs_srs, &mut thread_rng());
+
+        let acc_1 = Accumulator4::rand(&acc_srs);
+        Accumulator4::decide(&acc_srs, &acc_1);
+        let acc_2 = Accumulator4::rand(&acc_srs);
+        Accumulator4::decide(&acc_srs, &acc_2);
+
+        let mut prover_transcript = Transcript::new(b"hi");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let (instance, witness, proof) = Accumulator4::prove(&acc_srs, &acc_1, &acc_2, &mut prover_transcript);
+
+        let instance_expected = Accumulator4::verify(&acc_srs, &acc_1.instance, &acc_2.instance, &proof, &mut verifier_transcript);
+        assert_eq!(instance, instance_expected);
+
+        Accumulator4::decide(&acc_srs, &Accumulator4::new(&instance, &witness));
+    }
+
+    #[test]
+    fn test_trimmed_srs() {
+        let pcs_srs: KZH4SRS<E> = KZH4::setup(10, &mut thread_rng());
+        let acc_srs = Accumulator4::setup(pcs_srs, &mut thread_rng()).trim(6).unwrap();
+
+        let acc_1 = Accumulator4::rand(&acc_srs);
+        let acc_2 = Accumulator4::rand(&acc_srs);
+
+        let (instance, witness, _proof) = Accumulator4::prove(&acc_srs, &acc_1, &acc_2, &mut Transcript::new(b"hi"));
+
+        Accumulator4::decide(&acc_srs, &Accumulator4::new(&instance, &witness));
+    }
+}
@@ -1,12 +1,19 @@
 #![allow(warnings)]
 
+use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::pairing::Pairing;
-use ark_ec::AffineRepr;
+use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::SeedableRng;
 use ark_std::UniformRand;
-use rand::Rng;
+use rand::{Rng, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use std::fmt::Debug;
 
+use crate::kzh::KZH;
 use crate::math::Math;
+use crate::transcript::transcript::Transcript;
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
@@ -14,6 +21,65 @@ use rayon::iter::ParallelIterator;
 pub mod kzh2_fold;
 mod eq_tree;
 pub mod kzh3_fold;
+pub mod kzh4_fold;
+pub mod checkpoint;
+
+/// Common interface of the accumulation schemes for KZH openings, implemented by Accumulator2,
+/// Accumulator3 and Accumulator4 so that protocols can be generic over the KZH dimension
+pub trait AccumulationScheme<E: Pairing>: Sized + Clone + Debug + PartialEq + CanonicalSerialize + CanonicalDeserialize
+where
+    <E as Pairing>::ScalarField: Absorb,
+{
+    /// the dimension of the KZH scheme, it identifies the scheme in checkpoints
+    const DIMENSION: u32;
+
+    /// the polynomial commitment scheme whose openings are accumulated
+    type PCS: KZH<E>;
+    type SRS: Clone + CanonicalSerialize + CanonicalDeserialize;
+    type Instance: Clone + Debug + PartialEq + Eq + CanonicalSerialize + CanonicalDeserialize;
+    type Witness: Clone + Debug + PartialEq + Eq + CanonicalSerialize + CanonicalDeserialize;
+    /// the cross term sent by the prover when folding two accumulators
+    type Proof: Clone + Debug + PartialEq + Eq;
+
+    fn setup<R: RngCore>(pc_srs: <Self::PCS as KZH<E>>::SRS, rng: &mut R) -> Self::SRS;
+
+    /// Returns the fresh accumulator, i.e. with a zero error term, of an opening of com at input to output
+    fn from_opening(
+        srs: &Self::SRS,
+        input: &[E::ScalarField],
+        output: &E::ScalarField,
+        com: &<Self::PCS as KZH<E>>::Commitment,
+        open: <Self::PCS as KZH<E>>::Opening,
+    ) -> Self;
+
+    fn instance(&self) -> &Self::Instance;
+
+    fn witness(&self) -> &Self::Witness;
+
+    /// Folds two accumulators, returns the folded accumulator and the proof for the verifier
+    fn prove(
+        srs: &Self::SRS,
+        acc_1: &Self,
+        acc_2: &Self,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> (Self, Self::Proof);
+
+    /// Returns the folded instance given the two instances and the proof
+    fn verify(
+        srs: &Self::SRS,
+        instance_1: &Self::Instance,
+        instance_2: &Self::Instance,
+        proof: &Self::Proof,
+        transcript: &mut Transcript<E::ScalarField>,
+    ) -> Self::Instance;
+
+    /// Returns whether the accumulator is satisfied
+    fn decide(srs: &Self::SRS, acc: &Self) -> bool;
+
+    /// Returns whether the sizes of the accumulator match the srs, decide may panic otherwise, e.g.
+    /// on an accumulator deserialized from untrusted data
+    fn has_valid_sizes(srs: &Self::SRS, acc: &Self) -> bool;
+}
 
 /// returns a vector of length "degree" of E::G1Affine random elements
 fn generate_random_elements<E: Pairing, R: Rng>(degree: usize, rng: &mut R) -> Vec<E::G1Affine> {
@@ -24,6 +90,27 @@ fn generate_random_elements<E: Pairing, R: Rng>(degree: usize, rng: &mut R) -> V
     elements
 }
 
+/// Derives `n` generators of G1 from a domain-separation label and the name of the generators, the
+/// same way PedersenCommitment::setup does: the inputs are hashed with SHAKE256 into the seed of a
+/// ChaCha20Rng, so nobody knows discrete-log relations among them and anyone can recompute them.
+/// The generators for a smaller n are a prefix of the ones for a larger n, which keeps trimming valid
+pub fn derive_generators<E: Pairing>(label: &[u8], name: &[u8], n: usize) -> Vec<E::G1Affine> {
+    let mut shake = sha3::Shake256::default();
+    shake.update(&(label.len() as u64).to_le_bytes());
+    shake.update(label);
+    shake.update(name);
+    let mut buf = vec![];
+    E::G1Affine::generator().serialize_compressed(&mut buf).unwrap();
+    shake.update(&buf);
+
+    let mut reader = shake.finalize_xof();
+    let mut seed = [0u8; 32];
+    reader.read(&mut seed);
+    let mut rng = rand_chacha::ChaCha20Rng::from_seed(seed);
+
+    generate_random_elements::<E, _>(n, &mut rng)
+}
+
 use rayon::prelude::*; // Ensure you import rayon for parallel iterators
 
 pub fn generic_linear_combination<T, FN>(vec1: &[T], vec2: &[T], combine_fn: FN) -> Vec<T>
@@ -40,3 +127,107 @@ where
         .map(|(&a, &b)| combine_fn(a, b))
         .collect()
 }
+
+/// Returns sum_i coeffs[i] * vectors[i], the k-ary version of generic_linear_combination used by
+/// the multi-folding
+pub fn weighted_sum<F: PrimeField>(vectors: &[&[F]], coeffs: &[F]) -> Vec<F> {
+    assert_eq!(vectors.len(), coeffs.len(), "number of vectors and coefficients must match");
+    assert!(!vectors.is_empty(), "there should be at least one vector");
+    assert!(vectors.iter().all(|v| v.len() == vectors[0].len()), "Vectors must have the same length.");
+
+    (0..vectors[0].len())
+        .into_par_iter()
+        .map(|j| vectors.iter().zip(coeffs.iter()).map(|(v, c)| v[j] * c).sum())
+        .collect()
+}
+
+/// Same as weighted_sum for vectors of group elements
+pub fn weighted_sum_of_points<G: CurveGroup>(vectors: &[&[G]], coeffs: &[G::ScalarField]) -> Vec<G> {
+    assert_eq!(vectors.len(), coeffs.len(), "number of vectors and coefficients must match");
+    assert!(!vectors.is_empty(), "there should be at least one vector");
+    assert!(vectors.iter().all(|v| v.len() == vectors[0].len()), "Vectors must have the same length.");
+
+    (0..vectors[0].len())
+        .into_par_iter()
+        .map(|j| vectors.iter().zip(coeffs.iter()).fold(G::ZERO, |acc, (v, c)| acc + v[j] * c))
+        .collect()
+}
+
+/// Evaluates at `point` the Lagrange basis of the integer domain {start, start + 1, ..., start + n - 1}
+pub fn lagrange_basis_at<F: PrimeField>(start: usize, n: usize, point: F) -> Vec<F> {
+    let domain: Vec<F> = (start..start + n).map(|i| F::from(i as u64)).collect();
+
+    domain.iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            let (numerator, denominator) = domain.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold((F::ONE, F::ONE), |(num, den), (_, x_j)| (num * (point - x_j), den * (*x_i - x_j)));
+            numerator * denominator.inverse().unwrap()
+        })
+        .collect()
+}
+
+/// Evaluates at `point` the polynomial X (X - 1) ... (X - k + 1) vanishing on the folding domain {0, ..., k - 1}
+pub fn vanishing_polynomial_at<F: PrimeField>(k: usize, point: F) -> F {
+    (0..k).map(|i| point - F::from(i as u64)).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::KZH;
+    use crate::kzh_fold::kzh2_fold::Accumulator2;
+    use crate::kzh_fold::kzh3_fold::Accumulator3;
+    use crate::kzh_fold::kzh4_fold::Accumulator4;
+    use crate::kzh_fold::AccumulationScheme;
+    use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
+    use crate::transcript::transcript::Transcript;
+    use ark_std::UniformRand;
+
+    fn fresh_accumulator<A: AccumulationScheme<E>>(pc_srs: &<A::PCS as KZH<E>>::SRS, srs: &A::SRS) -> A {
+        let num_vars = <A::PCS as KZH<E>>::num_variables(pc_srs);
+
+        let polynomial = MultilinearPolynomial::rand(num_vars, &mut thread_rng());
+        let input: Vec<F> = (0..num_vars).map(|_| F::rand(&mut thread_rng())).collect();
+        let output = polynomial.evaluate(input.as_slice());
+
+        let (com, hint) = <A::PCS as KZH<E>>::commit(pc_srs, &polynomial);
+        let open = <A::PCS as KZH<E>>::open(pc_srs, input.as_slice(), &hint, &polynomial);
+
+        A::from_opening(srs, input.as_slice(), &output, &com, open)
+    }
+
+    fn test_accumulation_scheme_helper<A: AccumulationScheme<E>>(num_vars: usize) {
+        let pc_srs = <A::PCS as KZH<E>>::setup(num_vars, &mut thread_rng());
+        let srs = A::setup(pc_srs.clone(), &mut thread_rng());
+
+        let acc_1: A = fresh_accumulator(&pc_srs, &srs);
+        let acc_2: A = fresh_accumulator(&pc_srs, &srs);
+        assert!(A::decide(&srs, &acc_1));
+        assert!(A::decide(&srs, &acc_2));
+
+        let mut prover_transcript = Transcript::new(b"test");
+        let mut verifier_transcript = prover_transcript.clone();
+
+        let (acc, proof) = A::prove(&srs, &acc_1, &acc_2, &mut prover_transcript);
+        let instance = A::verify(&srs, acc_1.instance(), acc_2.instance(), &proof, &mut verifier_transcript);
+        assert_eq!(&instance, acc.instance());
+        assert!(A::decide(&srs, &acc));
+
+        // fold a fresh accumulator into the running one
+        let acc_3: A = fresh_accumulator(&pc_srs, &srs);
+        let (acc, _proof) = A::prove(&srs, &acc, &acc_3, &mut prover_transcript);
+        assert!(A::decide(&srs, &acc));
+    }
+
+    #[test]
+    fn test_accumulation_scheme() {
+        test_accumulation_scheme_helper::<Accumulator2<E>>(6);
+        test_accumulation_scheme_helper::<Accumulator3<E>>(6);
+        test_accumulation_scheme_helper::<Accumulator4<E>>(8);
+    }
+}
@@ -1,6 +1,7 @@
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use crate::kzh::errors::PCSError;
 use crate::kzh::KZH;
 use crate::polynomial::multilinear_poly::multilinear_poly::MultilinearPolynomial;
 use crate::transcript::transcript::AppendToTranscript;
@@ -18,9 +19,19 @@ where
     + CanonicalSerialize
     + CanonicalDeserialize;
 
+    /// Prover-only data output alongside the commitment, needed to open it later
+    type VectorCommitmentHint;
+
     type CommitmentKey;
 
-    fn commit(vec: &[E::ScalarField], ck: &Self::CommitmentKey) -> Self::VectorCommitment;
+    /// Proof that a single entry of a committed vector has a given value
+    type VectorOpening;
+
+    fn commit(vec: &[E::ScalarField], ck: &Self::CommitmentKey) -> (Self::VectorCommitment, Self::VectorCommitmentHint);
+
+    fn open_index(vec: &[E::ScalarField], hint: &Self::VectorCommitmentHint, index: usize, ck: &Self::CommitmentKey) -> Self::VectorOpening;
+
+    fn verify_index(com: &Self::VectorCommitment, index: usize, value: &E::ScalarField, opening: &Self::VectorOpening, ck: &Self::CommitmentKey) -> Result<(), PCSError>;
 }
 
 impl<E: Pairing, PC: KZH<E>> VectorCommitmentScheme<E> for PC
@@ -28,9 +39,54 @@ where
     <E as Pairing>::ScalarField: Absorb,
 {
     type VectorCommitment = PC::Commitment;
+    type VectorCommitmentHint = PC::CommitmentHint;
     type CommitmentKey = PC::SRS;
-    fn commit(vec: &[<E>::ScalarField], srs: &Self::CommitmentKey) -> Self::VectorCommitment {
+    type VectorOpening = PC::Opening;
+    fn commit(vec: &[<E>::ScalarField], srs: &Self::CommitmentKey) -> (Self::VectorCommitment, Self::VectorCommitmentHint) {
         let poly = MultilinearPolynomial::new(vec.to_vec());
         PC::commit(srs, &poly)
     }
+
+    fn open_index(vec: &[E::ScalarField], hint: &Self::VectorCommitmentHint, index: usize, srs: &Self::CommitmentKey) -> Self::VectorOpening {
+        let poly = MultilinearPolynomial::new(vec.to_vec());
+        PC::open_boolean(srs, index, hint, &poly)
+    }
+
+    fn verify_index(com: &Self::VectorCommitment, index: usize, value: &E::ScalarField, opening: &Self::VectorOpening, srs: &Self::CommitmentKey) -> Result<(), PCSError> {
+        PC::verify_boolean(srs, index, value, com, opening)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use crate::constant_for_curves::{ScalarField as F, E};
+    use crate::kzh::kzh2::KZH2;
+    use crate::kzh::kzh3::KZH3;
+    use crate::kzh::KZH;
+    use crate::nexus_spartan::commitment_traits::VectorCommitmentScheme;
+    use ark_std::UniformRand;
+
+    fn test_open_index_helper<PC: KZH<E>>() {
+        let srs = PC::setup(8, &mut thread_rng());
+        let vec: Vec<F> = (0..256).map(|_| F::rand(&mut thread_rng())).collect();
+        let (com, hint) = <PC as VectorCommitmentScheme<E>>::commit(vec.as_slice(), &srs);
+
+        for index in [0, 77, 255] {
+            let opening = PC::open_index(vec.as_slice(), &hint, index, &srs);
+            PC::verify_index(&com, index, &vec[index], &opening, &srs).unwrap();
+            assert!(PC::verify_index(&com, index, &(vec[index] + F::from(1u64)), &opening, &srs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_open_index_kzh2() {
+        test_open_index_helper::<KZH2<E>>();
+    }
+
+    #[test]
+    fn test_open_index_kzh3() {
+        test_open_index_helper::<KZH3<E>>();
+    }
 }
\ No newline at end of file
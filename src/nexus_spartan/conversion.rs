@@ -93,10 +93,11 @@ where
     PC: KZH<E>,
     <E as Pairing>::ScalarField: Absorb,
 {
+    /// Returns the instance together with the hint of the witness commitment, which the prover needs to open it
     pub fn convert<G: SWCurveConfig>(
         cs: ConstraintSystemRef<G::ScalarField>,
         key: &PC::SRS,
-    ) -> Self where
+    ) -> (Self, PC::CommitmentHint) where
         E: Pairing<G1Affine=Affine<G>, ScalarField=G::ScalarField>,
     {
         let cs_borrow = cs.borrow().unwrap();
@@ -109,13 +110,16 @@ where
 
         let poly_W = MultilinearPolynomial::new(witness);
         let commit_timer = start_timer!(|| "Instance conversion (commit to witness)");
-        let comm_W = PC::commit(&key,&poly_W);
+        let (comm_W, comm_W_hint) = PC::commit(&key,&poly_W);
         end_timer!(commit_timer);
 
-        CRR1CSInstance {
-            input: Assignment::new(&pub_io[1..]).unwrap(),
-            comm_W,
-        }
+        (
+            CRR1CSInstance {
+                input: Assignment::new(&pub_io[1..]).unwrap(),
+                comm_W,
+            },
+            comm_W_hint,
+        )
     }
 }
 
@@ -220,7 +224,7 @@ pub mod tests {
         // convert to the corresponding Spartan types
         let shape = CRR1CSShape::<ScalarField>::convert::<G1>(cs.clone());
         let SRS: KZH2SRS<E> = KZH2::setup(4, &mut thread_rng());
-        let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+        let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
         let witness = CRR1CSWitness::<ScalarField>::convert(cs.clone());
         // check that the Spartan instance-witness pair is still satisfying
         assert!(is_sat(&shape, &instance, &witness, &SRS).unwrap());
@@ -237,6 +241,7 @@ pub mod tests {
             &shape,
             &instance,
             witness,
+            &hint,
             &SRS,
             &mut prover_transcript,
         );
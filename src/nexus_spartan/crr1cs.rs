@@ -126,7 +126,7 @@ pub fn check_commitments<E: Pairing, PC: KZH<E>>(
 
     let poly_W = MultilinearPolynomial::new(W);
 
-    let expected_comm_W = PC::commit(&key, &poly_W);
+    let (expected_comm_W, _) = PC::commit(&key, &poly_W);
 
     expected_comm_W == *comm_W
 }
@@ -146,7 +146,7 @@ pub fn is_sat<E: Pairing, PC: KZH<E>>(
 }
 
 #[allow(clippy::type_complexity)]
-// This produces a random satisfying structure, instance, witness, and public parameters for testing and benchmarking purposes.
+// This produces a random satisfying structure, instance, witness, witness commitment hint, and public parameters for testing and benchmarking purposes.
 pub fn produce_synthetic_crr1cs<E: Pairing, PC: KZH<E>>(
     num_cons: usize,
     num_vars: usize,
@@ -155,6 +155,7 @@ pub fn produce_synthetic_crr1cs<E: Pairing, PC: KZH<E>>(
     CRR1CSShape<E::ScalarField>,
     CRR1CSInstance<E, PC>,
     CRR1CSWitness<E::ScalarField>,
+    PC::CommitmentHint,
     CRSNARKKey<E, PC>,
 ) where
     <E as Pairing>::ScalarField: Absorb,
@@ -184,7 +185,7 @@ pub fn produce_synthetic_crr1cs<E: Pairing, PC: KZH<E>>(
     let gens = CRSNARKKey::<E, PC>::new(&SRS, num_cons, num_vars, num_inputs, num_cons);
 
     // compute commitments to the vectors `vars` and `E`.
-    let comm_W = <PC as VectorCommitmentScheme<E>>::commit(
+    let (comm_W, comm_W_hint) = <PC as VectorCommitmentScheme<E>>::commit(
         vars.assignment.as_slice(),
         &gens.gens_r1cs_sat,
     );
@@ -198,6 +199,7 @@ pub fn produce_synthetic_crr1cs<E: Pairing, PC: KZH<E>>(
         CRR1CSWitness::<E::ScalarField> {
             W: vars.clone(),
         },
+        comm_W_hint,
         gens,
     )
 }
\ No newline at end of file
@@ -240,6 +240,7 @@ impl<E: Pairing<ScalarField=F>, PC: KZH<E>, F: PrimeField + Absorb> CRR1CSProof<
         shape: &CRR1CSShape<F>,
         instance: &CRR1CSInstance<E, PC>,
         witness: CRR1CSWitness<F>,
+        hint: &PC::CommitmentHint,
         srs: &PC::SRS,
         transcript: &mut Transcript<F>,
     ) -> (CRR1CSProof<E, PC, F>, Vec<F>, Vec<F>) {
@@ -358,7 +359,7 @@ impl<E: Pairing<ScalarField=F>, PC: KZH<E>, F: PrimeField + Absorb> CRR1CSProof<
             PC::open(
                 &srs,
                 &ry[1..],
-                comm_W,
+                hint,
                 &poly_vars,
             )
         };
@@ -481,7 +482,7 @@ impl<E: Pairing<ScalarField=F>, PC: KZH<E>, F: PrimeField + Absorb> CRR1CSProof<
             &self.eval_vars_at_ry,
             comm_W,
             &self.proof_eval_vars_at_ry,
-        );
+        )?;
 
         Ok(())
     }
@@ -497,6 +498,7 @@ mod tests {
     use ark_ff::PrimeField;
     use ark_std::test_rng;
     use crate::kzh::kzh2::KZH2;
+    use crate::kzh::pst13::PST13;
 
     fn produce_tiny_r1cs<F: PrimeField + Absorb>() -> (R1CSInstance<F>, Vec<F>, Vec<F>) {
         // three constraints over five variables Z1, Z2, Z3, Z4, and Z5
@@ -582,6 +584,11 @@ mod tests {
         check_crr1cs_proof_helper::<E, KZH2<E>>()
     }
 
+    #[test]
+    pub fn check_crr1cs_proof_pst13() {
+        check_crr1cs_proof_helper::<E, PST13<E>>()
+    }
+
     fn check_crr1cs_proof_helper<E: Pairing, PC: KZH<E>>()
     where
         <E as Pairing>::ScalarField: Absorb,
@@ -589,7 +596,7 @@ mod tests {
         let num_vars = 1024;
         let num_cons = num_vars;
         let num_inputs = 10;
-        let (shape, instance, witness, gens) = produce_synthetic_crr1cs::<E, PC>(num_cons, num_vars, num_inputs);
+        let (shape, instance, witness, hint, gens) = produce_synthetic_crr1cs::<E, PC>(num_cons, num_vars, num_inputs);
         assert!(is_sat(&shape, &instance, &witness, &gens.gens_r1cs_sat).unwrap());
 
         let (num_cons, num_vars, _num_inputs) = (
@@ -604,6 +611,7 @@ mod tests {
             &shape,
             &instance,
             witness,
+            &hint,
             &gens.gens_r1cs_sat,
             &mut prover_transcript,
         );
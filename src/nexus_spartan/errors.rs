@@ -2,6 +2,7 @@ use ark_serialize::SerializationError;
 use ark_std::{error::Error, fmt::Display};
 use core::fmt::Debug;
 use thiserror::Error;
+use crate::kzh::errors::PCSError;
 
 #[derive(Error, Debug, Default)]
 pub enum ProofVerifyError {
@@ -10,8 +11,8 @@ pub enum ProofVerifyError {
     InternalError,
     #[error("Compressed group element failed to decompress: {0:?}")]
     DecompressionError([u8; 32]),
-    #[error("PolyCommitment error")]
-    PolyCommitmentError,
+    #[error("PolyCommitment error: {0}")]
+    PolyCommitmentError(#[from] PCSError),
 }
 
 #[derive(Debug)]
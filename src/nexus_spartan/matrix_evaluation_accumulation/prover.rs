@@ -197,7 +197,7 @@ pub mod tests {
         // convert to the corresponding Spartan types
         let shape = CRR1CSShape::<F>::convert::<G1>(cs.clone());
         let SRS: KZH2SRS<E> = KZH2::setup(4, &mut thread_rng());
-        let instance: CRR1CSInstance<E, KZH2<E>> = CRR1CSInstance::convert(cs.clone(), &SRS);
+        let (instance, hint): (CRR1CSInstance<E, KZH2<E>>, _) = CRR1CSInstance::convert(cs.clone(), &SRS);
         let witness = CRR1CSWitness::<F>::convert(cs.clone());
         // check that the Spartan instance-witness pair is still satisfying
         assert!(is_sat(&shape, &instance, &witness, &SRS).unwrap());
@@ -208,6 +208,7 @@ pub mod tests {
             &shape,
             &instance,
             witness,
+            &hint,
             &SRS,
             &mut prover_transcript,
         );
@@ -250,7 +250,7 @@ pub mod tests {
         let num_inputs = 10;
 
         // this generates a new instance/witness for spartan as well as PCS parameters
-        let (shape, instance, witness, gens) = produce_synthetic_crr1cs::<E, PC>(num_cons, num_vars, num_inputs);
+        let (shape, instance, witness, hint, gens) = produce_synthetic_crr1cs::<E, PC>(num_cons, num_vars, num_inputs);
         assert!(is_sat(&shape, &instance, &witness, &gens.gens_r1cs_sat).unwrap());
 
         let (num_cons, num_vars, _num_inputs) = (
@@ -265,6 +265,7 @@ pub mod tests {
             &shape,
             &instance,
             witness,
+            &hint,
             &gens.gens_r1cs_sat,
             &mut prover_transcript,
         );
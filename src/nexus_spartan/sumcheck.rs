@@ -304,6 +304,74 @@ impl<F: PrimeField + Absorb> SumcheckInstanceProof<F> {
             claims_dotp,
         )
     }
+
+    /// Proves claim = sum_b sum_i coeffs[i] * A_i(b) * B_i(b), i.e. a random linear combination of
+    /// several quadratic sumcheck instances sharing the same challenges.
+    /// Returns the proof, the challenges and the final evaluations of all A_i and B_i
+    pub fn prove_quad_batched<E>(
+        claim: &F,
+        num_rounds: usize,
+        poly_A_vec: &mut Vec<MultilinearPolynomial<F>>,
+        poly_B_vec: &mut Vec<MultilinearPolynomial<F>>,
+        coeffs: &[F],
+        transcript: &mut Transcript<F>,
+    ) -> (Self, Vec<F>, (Vec<F>, Vec<F>))
+    where
+        E: Pairing<ScalarField = F>,
+    {
+        assert_eq!(poly_A_vec.len(), poly_B_vec.len());
+        assert_eq!(poly_A_vec.len(), coeffs.len());
+
+        let mut e = *claim;
+        let mut r: Vec<F> = Vec::new();
+        let mut quad_polys: Vec<CompressedUniPoly<F>> = Vec::new();
+
+        for _j in 0..num_rounds {
+            let mut eval_point_0 = F::zero();
+            let mut eval_point_2 = F::zero();
+
+            for (coeff, (poly_A, poly_B)) in coeffs.iter().zip(poly_A_vec.iter().zip(poly_B_vec.iter())) {
+                let len = poly_A.len() / 2;
+                for i in 0..len {
+                    // eval 0: bound_func is A(low)
+                    eval_point_0 += *coeff * poly_A[i] * poly_B[i];
+
+                    // eval 2: bound_func is -A(low) + 2*A(high)
+                    let poly_A_bound_point = poly_A[len + i] + poly_A[len + i] - poly_A[i];
+                    let poly_B_bound_point = poly_B[len + i] + poly_B[len + i] - poly_B[i];
+                    eval_point_2 += *coeff * poly_A_bound_point * poly_B_bound_point;
+                }
+            }
+
+            let evals = vec![eval_point_0, e - eval_point_0, eval_point_2];
+            let poly = UniPoly::from_evals(&evals);
+
+            // append the prover's message to the transcript
+            <UniPoly<F> as AppendToTranscript<F>>::append_to_transcript(&poly, b"poly", transcript);
+
+            //derive the verifier's challenge for the next round
+            let r_j = Transcript::challenge_scalar(transcript, b"challenge_nextround");
+            r.push(r_j);
+
+            // bound all tables to the verifier's challenege
+            for (poly_A, poly_B) in poly_A_vec.iter_mut().zip(poly_B_vec.iter_mut()) {
+                poly_A.bound_poly_var_top(&r_j);
+                poly_B.bound_poly_var_top(&r_j);
+            }
+
+            e = poly.evaluate(&r_j);
+            quad_polys.push(poly.compress());
+        }
+
+        let poly_A_final = poly_A_vec.iter().map(|poly| poly[0]).collect();
+        let poly_B_final = poly_B_vec.iter().map(|poly| poly[0]).collect();
+
+        (
+            SumcheckInstanceProof::new(quad_polys),
+            r,
+            (poly_A_final, poly_B_final),
+        )
+    }
 }
 
 
@@ -7,7 +7,7 @@ use std::fmt::Debug;
 use ark_ff::{PrimeField};
 use ark_serialize::*;
 use rand::{Rng, RngCore};
-use crate::kzh_fold::generic_linear_combination;
+use crate::kzh_fold::{generic_linear_combination, weighted_sum};
 #[cfg(feature = "parallel")]
 use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
 use crate::math::Math;
@@ -278,6 +278,22 @@ impl<F: PrimeField> MultilinearPolynomial<F> {
             len: poly1.len,
         }
     }
+
+    /// Computes sum_i coeffs[i] * polys[i], used to fold several polynomials at once
+    pub fn weighted_sum(polys: &[&Self], coeffs: &[F]) -> Self {
+        assert!(
+            polys.iter().all(|poly| poly.num_variables == polys[0].num_variables),
+            "Polynomials must have the same number of variables"
+        );
+
+        let evaluations: Vec<&[F]> = polys.iter().map(|poly| poly.evaluation_over_boolean_hypercube.as_slice()).collect();
+
+        Self {
+            num_variables: polys[0].num_variables,
+            evaluation_over_boolean_hypercube: weighted_sum(evaluations.as_slice(), coeffs),
+            len: polys[0].len,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,5 @@
 use crate::kzh_fold::kzh2_fold::{Acc2Instance, Acc2SRS, Accumulator2 as KZHAccumulator, Accumulator2};
-use crate::kzh::kzh2::{KZH2Commitment, KZH2};
+use crate::kzh::kzh2::{KZH2Commitment, KZH2CommitmentHint, KZH2};
 use crate::kzh::KZH;
 use crate::nexus_spartan::sumcheck::SumcheckInstanceProof;
 use crate::polynomial::eq_poly::eq_poly::EqPolynomial;
@@ -81,9 +81,9 @@ where
         let b_2_poly = MultilinearPolynomial::random_binary(num_vars, rng);
         let c_poly = b_1_poly.get_bitfield_union_poly(&b_2_poly);
 
-        let B_1_commitment = KZH2::commit(&srs.pc_srs, &b_1_poly);
-        let B_2_commitment = KZH2::commit(&srs.pc_srs, &b_2_poly);
-        let C_commitment = KZH2::commit(&srs.pc_srs, &c_poly);
+        let (B_1_commitment, _) = KZH2::commit_sparse_poly(&srs.pc_srs, &b_1_poly);
+        let (B_2_commitment, _) = KZH2::commit_sparse_poly(&srs.pc_srs, &b_2_poly);
+        let (C_commitment, _) = KZH2::commit_sparse_poly(&srs.pc_srs, &c_poly);
 
         // Some random stuff for the sig/pk
         let sig = E::G2Affine::rand(rng);
@@ -129,6 +129,8 @@ where
     pub running_bitfield_poly: MultilinearPolynomial<E::ScalarField>,
     // Commitment to Alice's running bitfield
     pub running_bitfield_commitment: KZH2Commitment<E>,
+    // Prover hint of the commitment to Alice's running bitfield, used to update it incrementally
+    pub running_bitfield_hint: KZH2CommitmentHint<E>,
     // Alice's running accumulator
     pub running_accumulator: Accumulator2<E>,
     // running signature
@@ -193,7 +195,7 @@ where
     <<E as Pairing>::G1Affine as AffineRepr>::BaseField: Absorb + PrimeField,
     F: PrimeField + Absorb,
 {
-    let bitfield_commitment = KZH2::commit(
+    let (bitfield_commitment, bitfield_hint) = KZH2::commit_sparse_poly(
         &acc_srs.pc_srs,
         bitfield_poly,
     );
@@ -201,7 +203,7 @@ where
     let opening_proof = KZH2::open(
         &acc_srs.pc_srs,
         eval_point,
-        &bitfield_commitment,
+        &bitfield_hint,
         &bitfield_poly,
     );
 
@@ -301,7 +303,18 @@ where
         let b_2_poly = &self.bob_data.bitfield_poly;
 
         let c_poly = b_1_poly.get_bitfield_union_poly(&b_2_poly);
-        let C_commitment = KZH2::commit(&self.srs.acc_srs.pc_srs, &c_poly);
+
+        // c(x) only differs from b_1(x) on the bits that Bob adds, so update the running commitment
+        // on those positions instead of recommitting to the whole bitfield
+        let deltas: Vec<_> = c_poly.evaluation_over_boolean_hypercube.iter()
+            .zip(b_1_poly.evaluation_over_boolean_hypercube.iter())
+            .enumerate()
+            .filter(|(_, (c, b_1))| c != b_1)
+            .map(|(index, (c, b_1))| (index, *c - b_1))
+            .collect();
+        let mut C_commitment = self.running_bitfield_commitment.clone();
+        let mut C_hint = self.running_bitfield_hint.clone();
+        KZH2::update_commitment(&self.srs.acc_srs.pc_srs, &mut C_commitment, &mut C_hint, deltas.as_slice());
 
         // Step 3: Get r from verifier: it's the evaluation point challenge (for the zerocheck)
         transcript.append_point::<E>(
@@ -491,8 +504,8 @@ pub mod test {
 
         // Generate random running data for Alice
         let alice_bitfield = MultilinearPolynomial::random_binary(num_vars, rng);
-        let alice_bitfield_commitment = KZH2::commit(&srs.acc_srs.pc_srs, &alice_bitfield);
-        let alice_running_accumulator = Accumulator2::rand(&srs.acc_srs, rng);
+        let (alice_bitfield_commitment, alice_bitfield_hint) = KZH2::commit(&srs.acc_srs.pc_srs, &alice_bitfield);
+        let alice_running_accumulator = Accumulator2::rand(&srs.acc_srs, rng).unwrap();
         let alice_running_sig = G2Affine::rand(rng);
         let alice_running_pk = G1Affine::rand(rng);
 
@@ -502,13 +515,18 @@ pub mod test {
             srs: srs.clone(),
             running_bitfield_poly: alice_bitfield,
             running_bitfield_commitment: alice_bitfield_commitment,
+            running_bitfield_hint: alice_bitfield_hint,
             running_accumulator: alice_running_accumulator,
             running_signature: alice_running_sig,
             running_public_key: alice_running_pk,
             bob_data,
         };
 
-        let _aggregated_data = alice.aggregate(&mut transcript_p);
+        let aggregated_data = alice.aggregate(&mut transcript_p);
+
+        // the incrementally updated commitment must match a fresh commitment to the union bitfield
+        let (expected_commitment, _) = KZH2::commit(&srs.acc_srs.pc_srs, &aggregated_data.bitfield_poly);
+        assert_eq!(aggregated_data.bitfield_commitment, expected_commitment);
 
         //////////// Verification //////////////////
     }